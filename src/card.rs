@@ -0,0 +1,362 @@
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Suit {
+    Clubs,
+    Spades,
+    Hearts,
+    Diamonds,
+    Joker,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds];
+
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Clubs => 'a',
+            Suit::Spades => 'b',
+            Suit::Hearts => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Joker => 'j',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Suit> {
+        match letter {
+            'a' => Some(Suit::Clubs),
+            'b' => Some(Suit::Spades),
+            'c' => Some(Suit::Hearts),
+            'd' => Some(Suit::Diamonds),
+            _ => None,
+        }
+    }
+
+    /// Parses a suit in standard notation: its initial ("h"), symbol ("♥")
+    /// or name ("hearts"), in any case.
+    pub fn from_name(name: &str) -> Option<Suit> {
+        match name.to_lowercase().as_str() {
+            "c" | "♣" | "♧" | "club" | "clubs" => Some(Suit::Clubs),
+            "s" | "♠" | "♤" | "spade" | "spades" => Some(Suit::Spades),
+            "h" | "♥" | "♡" | "heart" | "hearts" => Some(Suit::Hearts),
+            "d" | "♦" | "♢" | "diamond" | "diamonds" => Some(Suit::Diamonds),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Suit::Clubs => "♣",
+            Suit::Spades => "♠",
+            Suit::Hearts => "♥",
+            Suit::Diamonds => "♦",
+            Suit::Joker => "★",
+        }
+    }
+
+    /// Letter standing in for the symbol where only ASCII can be shown.
+    pub fn ascii(&self) -> &'static str {
+        match self {
+            Suit::Clubs => "C",
+            Suit::Spades => "S",
+            Suit::Hearts => "H",
+            Suit::Diamonds => "D",
+            Suit::Joker => "*",
+        }
+    }
+
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Hearts | Suit::Diamonds)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Rank(u8);
+
+impl Rank {
+    pub const TWO: Rank = Rank(2);
+    pub const JACK: Rank = Rank(11);
+    pub const QUEEN: Rank = Rank(12);
+    pub const KING: Rank = Rank(13);
+    pub const ACE: Rank = Rank(14);
+    /// Jokers outrank every other card.
+    pub const JOKER: Rank = Rank(15);
+
+    pub fn new(value: u8) -> Option<Rank> {
+        if !(Rank::TWO.0..=Rank::ACE.0).contains(&value) {
+            return None;
+        }
+        Some(Rank(value))
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Parses a rank from its label ("Q", "10", "T" for ten) or name
+    /// ("queen", "ten"), in any case.
+    pub fn from_label(label: &str) -> Option<Rank> {
+        let value = match label.to_lowercase().as_str() {
+            "a" | "ace" => 14,
+            "k" | "king" => 13,
+            "q" | "queen" => 12,
+            "j" | "jack" => 11,
+            "t" | "ten" => 10,
+            "nine" => 9,
+            "eight" => 8,
+            "seven" => 7,
+            "six" => 6,
+            "five" => 5,
+            "four" => 4,
+            "three" => 3,
+            "two" => 2,
+            digits if digits.chars().all(|c| c.is_ascii_digit()) => digits.parse().ok()?,
+            _ => return None,
+        };
+        Rank::new(value)
+    }
+
+    /// Short label as printed on the card face, e.g. "Q" or "10".
+    pub fn label(&self) -> String {
+        match self.0 {
+            14 => String::from("A"),
+            13 => String::from("K"),
+            12 => String::from("Q"),
+            11 => String::from("J"),
+            15 => String::from("JK"),
+            value => value.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Card {
+    pub suit: Suit,
+    pub rank: Rank,
+}
+
+impl Card {
+    pub fn new(suit: Suit, rank: Rank) -> Card {
+        Card { suit, rank }
+    }
+
+    pub fn joker() -> Card {
+        Card::new(Suit::Joker, Rank::JOKER)
+    }
+
+    pub fn is_joker(&self) -> bool {
+        self.suit == Suit::Joker
+    }
+}
+
+impl Ord for Card {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank
+            .cmp(&other.rank)
+            .then_with(|| self.suit.cmp(&other.suit))
+    }
+}
+
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Accepts the internal notation ("c12", "jk") as well as standard notation:
+/// "QH", "10d", "Ts", "A♠", "♥K" and "queen of hearts".
+impl FromStr for Card {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Card, Error> {
+        let input = s.trim().to_lowercase();
+        if input == "jk" || input == "joker" {
+            return Ok(Card::joker());
+        }
+        let invalid = || Error::UnknownCard(s.to_string());
+        // A suit letter followed by digits is always the internal notation.
+        let re: Regex = Regex::new(r"^([abcd])(\d{1,2})$").unwrap();
+        if let Some(caps) = re.captures(&input) {
+            let suit = caps[1]
+                .chars()
+                .next()
+                .and_then(Suit::from_letter)
+                .ok_or_else(invalid)?;
+            let rank = caps[2]
+                .parse::<u8>()
+                .ok()
+                .and_then(Rank::new)
+                .ok_or_else(invalid)?;
+            return Ok(Card::new(suit, rank));
+        }
+        let (rank, suit) = match input.split_once(" of ") {
+            Some((rank, suit)) => (rank.trim(), suit.trim()),
+            None => {
+                let first = input.chars().next().ok_or_else(invalid)?;
+                let last = input.chars().next_back().ok_or_else(invalid)?;
+                if Suit::from_name(&first.to_string()).is_some() && first.len_utf8() > 1 {
+                    let (suit, rank) = input.split_at(first.len_utf8());
+                    (rank, suit)
+                } else {
+                    input.split_at(input.len() - last.len_utf8())
+                }
+            }
+        };
+        match (Rank::from_label(rank), Suit::from_name(suit)) {
+            (Some(rank), Some(suit)) => Ok(Card::new(suit, rank)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_joker() {
+            return write!(f, "jk");
+        }
+        write!(f, "{}{}", self.suit.letter(), self.rank.value())
+    }
+}
+
+impl Serialize for Rank {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rank {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Rank, D::Error> {
+        let value = u8::deserialize(deserializer)?;
+        Rank::new(value).ok_or_else(|| de::Error::custom(format!("invalid rank {}", value)))
+    }
+}
+
+/// Cards are stored in their short notation, e.g. "c12".
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Card, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn card_can_be_parsed() {
+        let card: Card = "c12".parse().unwrap();
+        assert_eq!(card, Card::new(Suit::Hearts, Rank::QUEEN));
+        let card: Card = "a2".parse().unwrap();
+        assert_eq!(card, Card::new(Suit::Clubs, Rank::TWO));
+    }
+
+    #[test]
+    fn card_accepts_standard_notation() {
+        let queen = Card::new(Suit::Hearts, Rank::QUEEN);
+        for s in [
+            "QH",
+            "qh",
+            "♥Q",
+            "Q♥",
+            "queen of hearts",
+            "Queen of Hearts",
+            "12 of h",
+        ] {
+            assert_eq!(s.parse(), Ok(queen), "{}", s);
+        }
+        let ten = Card::new(Suit::Diamonds, Rank::new(10).unwrap());
+        for s in ["10d", "TD", "td", "10♦", "♦10", "ten of diamonds"] {
+            assert_eq!(s.parse(), Ok(ten), "{}", s);
+        }
+        assert_eq!("A♠".parse(), Ok(Card::new(Suit::Spades, Rank::ACE)));
+        assert_eq!("♥K".parse(), Ok(Card::new(Suit::Hearts, Rank::KING)));
+        assert_eq!("2c".parse(), Ok(Card::new(Suit::Clubs, Rank::TWO)));
+        assert_eq!(" Joker ".parse(), Ok(Card::joker()));
+    }
+
+    #[test]
+    fn card_prefers_internal_notation() {
+        // "a14" is the ace of clubs, not an ace followed by "14".
+        assert_eq!("a14".parse(), Ok(Card::new(Suit::Clubs, Rank::ACE)));
+        assert_eq!(
+            "C5".parse(),
+            Ok(Card::new(Suit::Hearts, Rank::new(5).unwrap()))
+        );
+        assert_eq!("ac".parse(), Ok(Card::new(Suit::Clubs, Rank::ACE)));
+    }
+
+    #[test]
+    fn card_rejects_invalid_input() {
+        for s in [
+            "a1",
+            "b15",
+            "j15",
+            "bb",
+            "14",
+            "!14",
+            "f10",
+            "a100",
+            "",
+            "1h",
+            "15s",
+            "qx",
+            "♥",
+            "queen of",
+            "of hearts",
+        ] {
+            assert_eq!(s.parse::<Card>(), Err(Error::UnknownCard(s.to_string())));
+        }
+        let error = "qx".parse::<Card>().unwrap_err();
+        assert!(error.to_string().contains("A♠"));
+        assert!(error.ascii().is_ascii());
+    }
+
+    #[test]
+    fn card_display_round_trips() {
+        for s in ["a14", "b10", "c2", "d11", "jk"] {
+            let card: Card = s.parse().unwrap();
+            assert_eq!(card.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn cards_are_ordered_by_rank_first() {
+        let low: Card = "d3".parse().unwrap();
+        let high: Card = "a4".parse().unwrap();
+        assert!(low < high);
+        let clubs: Card = "a4".parse().unwrap();
+        assert!(clubs < "b4".parse().unwrap());
+    }
+
+    #[test]
+    fn jokers_outrank_aces() {
+        let joker: Card = "jk".parse().unwrap();
+        assert!(joker.is_joker());
+        assert!(joker > "d14".parse().unwrap());
+    }
+
+    #[test]
+    fn rank_has_face_labels() {
+        assert_eq!(Rank::ACE.label(), "A");
+        assert_eq!(Rank::JACK.label(), "J");
+        assert_eq!(Rank::new(10).unwrap().label(), "10");
+        assert!(Rank::new(1).is_none());
+        assert_eq!(Rank::from_label("t"), Rank::new(10));
+        assert_eq!(Rank::from_label("Jack"), Some(Rank::JACK));
+        assert_eq!(Rank::from_label("1"), None);
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+
+use crate::{Card, DeckSpec, Side};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The requested deck size cannot be built.
+    InvalidDeckSize(usize),
+    /// The deck composition is empty or contradictory.
+    InvalidDeckSpec(DeckSpec),
+    /// The input could not be parsed as a card.
+    UnknownCard(String),
+    /// The card is not part of the deck's composition.
+    CardNotInDeck(Card),
+    /// The card has already been drawn from the deck.
+    CardAlreadyRemoved(Card),
+    /// The card is still in the deck and cannot be returned to it.
+    CardAlreadyInDeck(Card),
+    /// The number of initial cards does not match the number of rows.
+    RowCountMismatch { rows: usize, cards: usize },
+    /// The operation would leave the row with this index without cards.
+    RowEmpty(usize),
+    /// The table has no row with this index.
+    RowNotFound(usize),
+    /// The table would have no rows.
+    NoRows,
+    /// The command needs the drawn card.
+    CardRequired,
+    /// There is no applied event left to undo.
+    NothingToUndo,
+    /// There is no undone event left to redo.
+    NothingToRedo,
+    /// The history has fewer events than requested.
+    HistoryOutOfRange(usize),
+    /// Reading or writing a file failed.
+    Io(String),
+    /// A saved session could not be parsed or replayed.
+    InvalidSave(String),
+    /// The input is not the name of a tie rule.
+    UnknownTieRule(String),
+    /// The input is not the name of a command.
+    UnknownCommand(String),
+    /// A script line is not a well-formed move.
+    InvalidMove(String),
+    /// The input is not the name of a built-in theme.
+    UnknownTheme(String),
+    /// The config file could not be parsed.
+    InvalidConfig(String),
+    /// The input is not the name of a rules variant.
+    UnknownRules(String),
+    /// The rules do not allow a guess on this end of the row.
+    GuessNotAllowed { row: usize, side: Side },
+    /// There are no cards left to draw.
+    DeckEmpty,
+    /// A script line failed.
+    Line { line: usize, error: Box<Error> },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidDeckSize(size) => write!(
+                f,
+                "Invalid deck size {}: must be a multiple of 4 no larger than 52",
+                size
+            ),
+            Error::InvalidDeckSpec(spec) => write!(f, "Invalid deck composition {:?}", spec),
+            Error::UnknownCard(input) => write!(
+                f,
+                "Unknown card \"{}\": expected e.g. {}",
+                input, CARD_EXAMPLES
+            ),
+            Error::CardNotInDeck(card) => write!(f, "Card {} is not in the deck", card),
+            Error::CardAlreadyRemoved(card) => {
+                write!(f, "Card {} has already been removed from the deck", card)
+            }
+            Error::CardAlreadyInDeck(card) => write!(f, "Card {} is already in the deck", card),
+            Error::RowCountMismatch { rows, cards } => {
+                write!(f, "Card amount ({}) must match row count ({})", cards, rows)
+            }
+            Error::RowEmpty(row) => write!(f, "Cannot remove last card in row {}", row + 1),
+            Error::RowNotFound(row) => write!(f, "Row {} does not exist", row + 1),
+            Error::NoRows => write!(f, "The table needs at least one row"),
+            Error::CardRequired => write!(f, "Command requires a card"),
+            Error::NothingToUndo => write!(f, "Nothing to undo"),
+            Error::NothingToRedo => write!(f, "Nothing to redo"),
+            Error::HistoryOutOfRange(position) => {
+                write!(f, "History has no position {}", position)
+            }
+            Error::Io(message) => write!(f, "I/O error: {}", message),
+            Error::InvalidSave(message) => write!(f, "Invalid saved session: {}", message),
+            Error::UnknownTieRule(input) => write!(
+                f,
+                "Unknown tie rule \"{}\": expected lose, push or higher-or-equal",
+                input
+            ),
+            Error::UnknownCommand(input) => write!(
+                f,
+                "Unknown command \"{}\": expected c, al, ar, dl, dr, x, hl, hr, ll or lr",
+                input
+            ),
+            Error::InvalidMove(input) => {
+                write!(
+                    f,
+                    "Invalid move \"{}\": expected <row> <command> [card]",
+                    input
+                )
+            }
+            Error::UnknownTheme(name) => write!(
+                f,
+                "Unknown theme \"{}\": expected one of {}",
+                name,
+                crate::theme::PRESETS.join(", ")
+            ),
+            Error::InvalidConfig(message) => write!(f, "Invalid config: {}", message),
+            Error::UnknownRules(input) => write!(
+                f,
+                "Unknown rules \"{}\": expected classic or target-N, e.g. target-5",
+                input
+            ),
+            Error::GuessNotAllowed { row, side } => write!(
+                f,
+                "The rules allow no guess on the {} end of row {}",
+                side,
+                row + 1
+            ),
+            Error::DeckEmpty => write!(f, "The deck has no cards left to draw"),
+            Error::Line { line, error } => write!(f, "Line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+const CARD_EXAMPLES: &str = "QH, 10d, Ts, A♠, ♥K, queen of hearts or c12";
+const ASCII_CARD_EXAMPLES: &str = "QH, 10d, Ts, queen of hearts or c12";
+
+impl Error {
+    /// The message with examples that only use ASCII, for `--ascii`.
+    pub fn ascii(&self) -> String {
+        match self {
+            Error::UnknownCard(input) => format!(
+                "Unknown card \"{}\": expected e.g. {}",
+                input, ASCII_CARD_EXAMPLES
+            ),
+            Error::Line { line, error } => format!("Line {}: {}", line, error.ascii()),
+            e => e.to_string(),
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rules::{Classic, Rules};
+use crate::{recommend, Card, Command, Deck, Error, Guess, Outcome, Recommendation, Side, Table};
+
+/// A command applied to a row, with everything needed to revert it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub row: usize,
+    pub command: Command,
+    /// The drawn card for collapse and add commands, the removed card for
+    /// remove commands.
+    pub card: Card,
+    /// The row's cards before a collapse.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collapsed: Vec<Card>,
+}
+
+/// A deck and table with an undoable history of commands.
+pub struct Game {
+    initial: Vec<Vec<Card>>,
+    deck: Deck,
+    table: Table,
+    rules: Box<dyn Rules>,
+    history: Vec<Event>,
+    position: usize,
+}
+
+impl Game {
+    /// Starts a game under the [`Classic`] rules; the table's cards must
+    /// already be removed from the deck.
+    pub fn new(deck: Deck, table: Table) -> Game {
+        Game {
+            initial: table.rows.iter().map(|r| r.cards.clone()).collect(),
+            deck,
+            table,
+            rules: Box::new(Classic),
+            history: Vec::new(),
+            position: 0,
+        }
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn rules(&self) -> &dyn Rules {
+        self.rules.as_ref()
+    }
+
+    pub fn set_rules(&mut self, rules: Box<dyn Rules>) {
+        self.rules = rules;
+    }
+
+    /// The table's score under the rules, if they keep one.
+    pub fn score(&self) -> Option<u32> {
+        self.rules.score(&self.table)
+    }
+
+    /// Recommendations for every row end the rules allow a guess on, best
+    /// move first.
+    pub fn recommend(&self) -> Vec<Recommendation> {
+        recommend(&self.deck, &self.table)
+            .into_iter()
+            .filter(|r| self.rules.allows(&self.table.rows[r.row], r.side))
+            .collect()
+    }
+
+    /// The score, if the rules keep one, and the best move, if any, as shown
+    /// below the table.
+    pub fn status_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.rules.score_line(&self.table).into_iter().collect();
+        if let Some(best) = self.recommend().first() {
+            lines.push(format!(
+                "Best move: row {} {}, guess {} ({:.2})",
+                best.row + 1,
+                best.side,
+                best.guess,
+                best.chance.to_f32()
+            ));
+        }
+        lines
+    }
+
+    /// The table's rows before the first event.
+    pub fn initial(&self) -> &[Vec<Card>] {
+        &self.initial
+    }
+
+    /// Every recorded event, including undone ones that can be redone.
+    pub fn history(&self) -> &[Event] {
+        &self.history
+    }
+
+    /// Number of events currently applied.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Applies the command to the row and records it, discarding any undone
+    /// events. Collapse and add commands need the drawn card.
+    pub fn apply(&mut self, row: usize, command: Command, card: Option<Card>) -> Result<(), Error> {
+        let cards = match self.table.rows.get(row) {
+            Some(r) => r,
+            None => return Err(Error::RowNotFound(row)),
+        };
+        let (card, collapsed) = match command {
+            Command::RemoveLeft | Command::RemoveRight if cards.len() < 2 => {
+                return Err(Error::RowEmpty(row))
+            }
+            Command::RemoveLeft => (*cards.get_left(), Vec::new()),
+            Command::RemoveRight => (*cards.get_right(), Vec::new()),
+            Command::Collapse => match card {
+                Some(card) => (card, cards.cards.clone()),
+                None => return Err(Error::CardRequired),
+            },
+            Command::AddLeft | Command::AddRight | Command::Discard => match card {
+                Some(card) => (card, Vec::new()),
+                None => return Err(Error::CardRequired),
+            },
+        };
+        let event = Event {
+            row,
+            command,
+            card,
+            collapsed,
+        };
+        self.forward(&event)?;
+        self.history.truncate(self.position);
+        self.history.push(event);
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Bets on the end of the row against the drawn card and applies the
+    /// result under the rules and the deck's tie rule, as
+    /// [`Command::from_outcome`].
+    pub fn guess(
+        &mut self,
+        row: usize,
+        side: Side,
+        guess: Guess,
+        card: Card,
+    ) -> Result<Outcome, Error> {
+        let base = match self.table.rows.get(row) {
+            Some(r) if !self.rules.allows(r, side) => {
+                return Err(Error::GuessNotAllowed { row, side })
+            }
+            Some(r) => *r.get(side),
+            None => return Err(Error::RowNotFound(row)),
+        };
+        let outcome = self
+            .rules
+            .outcome(self.deck.tie_rule(), guess, &base, &card);
+        self.apply(row, Command::from_outcome(outcome, side), Some(card))?;
+        Ok(outcome)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.position < self.history.len()
+    }
+
+    pub fn undo(&mut self) -> Result<(), Error> {
+        if !self.can_undo() {
+            return Err(Error::NothingToUndo);
+        }
+        let event = self.history[self.position - 1].clone();
+        self.backward(&event)?;
+        self.position -= 1;
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), Error> {
+        if !self.can_redo() {
+            return Err(Error::NothingToRedo);
+        }
+        let event = self.history[self.position].clone();
+        self.forward(&event)?;
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Undoes or redoes events until `position` events are applied.
+    pub fn seek(&mut self, position: usize) -> Result<(), Error> {
+        if position > self.history.len() {
+            return Err(Error::HistoryOutOfRange(position));
+        }
+        while self.position > position {
+            self.undo()?;
+        }
+        while self.position < position {
+            self.redo()?;
+        }
+        Ok(())
+    }
+
+    fn forward(&mut self, event: &Event) -> Result<(), Error> {
+        let deck = &mut self.deck;
+        let row = &mut self.table.rows[event.row];
+        match event.command {
+            Command::RemoveLeft => row.remove_left(deck),
+            Command::RemoveRight => row.remove_right(deck),
+            Command::Collapse => {
+                deck.remove(&event.card)?;
+                row.collapse(event.card, deck).inspect_err(|_| {
+                    deck.add(event.card).unwrap();
+                })
+            }
+            Command::AddLeft => {
+                deck.remove(&event.card)?;
+                row.add_left(event.card);
+                Ok(())
+            }
+            Command::AddRight => {
+                deck.remove(&event.card)?;
+                row.add_right(event.card);
+                Ok(())
+            }
+            Command::Discard => deck.remove(&event.card),
+        }
+    }
+
+    fn backward(&mut self, event: &Event) -> Result<(), Error> {
+        let deck = &mut self.deck;
+        let row = &mut self.table.rows[event.row];
+        match event.command {
+            Command::RemoveLeft => {
+                deck.remove(&event.card)?;
+                row.add_left(event.card);
+            }
+            Command::RemoveRight => {
+                deck.remove(&event.card)?;
+                row.add_right(event.card);
+            }
+            Command::Collapse => {
+                for c in event.collapsed.iter() {
+                    deck.remove(c)?;
+                }
+                row.cards = event.collapsed.clone();
+                deck.add(event.card)?;
+            }
+            Command::AddLeft => {
+                row.remove_left(deck)?;
+            }
+            Command::AddRight => {
+                row.remove_right(deck)?;
+            }
+            Command::Discard => deck.add(event.card)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::{card, deal};
+    use crate::rules::TargetLength;
+    use crate::TieRule;
+
+    fn game() -> Game {
+        let (deck, table) = deal(16, &["a14", "b11"]);
+        Game::new(deck, table)
+    }
+
+    fn snapshot(game: &Game) -> (usize, Vec<String>) {
+        let rows = game
+            .table()
+            .rows
+            .iter()
+            .map(|r| format!("{:?}", r.cards))
+            .collect();
+        (game.deck().size(), rows)
+    }
+
+    #[test]
+    fn game_applies_commands() {
+        let mut game = game();
+        game.apply(0, Command::AddRight, Some(card("c13"))).unwrap();
+        game.apply(1, Command::AddLeft, Some(card("d12"))).unwrap();
+        assert_eq!(game.table().rows[0].cards, vec![card("a14"), card("c13")]);
+        assert_eq!(game.table().rows[1].cards, vec![card("d12"), card("b11")]);
+        assert_eq!(game.deck().size(), 12);
+        assert!(!game.deck().has_card(&card("c13")));
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn game_applies_guesses() {
+        let mut game = game();
+        assert_eq!(
+            game.guess(0, Side::Right, Guess::Lower, card("c13")),
+            Ok(Outcome::Win)
+        );
+        assert_eq!(game.table().rows[0].cards, vec![card("a14"), card("c13")]);
+        assert_eq!(
+            game.guess(1, Side::Left, Guess::Higher, card("c11")),
+            Ok(Outcome::Lose)
+        );
+        assert_eq!(game.table().rows[1].cards, vec![card("c11")]);
+        game.deck.set_tie_rule(TieRule::Push);
+        assert_eq!(
+            game.guess(1, Side::Left, Guess::Higher, card("d11")),
+            Ok(Outcome::Push)
+        );
+        assert_eq!(game.table().rows[1].cards, vec![card("c11")]);
+        assert!(!game.deck().has_card(&card("d11")));
+        assert_eq!(game.history()[2].command, Command::Discard);
+        game.undo().unwrap();
+        assert!(game.deck().has_card(&card("d11")));
+        assert_eq!(
+            game.guess(2, Side::Left, Guess::Higher, card("d11")),
+            Err(Error::RowNotFound(2))
+        );
+        assert_eq!(
+            game.guess(0, Side::Left, Guess::Higher, card("c13")),
+            Err(Error::CardAlreadyRemoved(card("c13")))
+        );
+    }
+
+    #[test]
+    fn game_follows_its_rules() {
+        let mut game = game();
+        assert_eq!(game.rules().name(), "classic");
+        assert_eq!(game.score(), None);
+        assert_eq!(game.status_lines().len(), 1);
+        assert!(game.status_lines()[0].starts_with("Best move: row "));
+        game.set_rules(Box::new(TargetLength { length: 2 }));
+        assert_eq!(game.score(), Some(0));
+        assert_eq!(game.status_lines()[0], "Score under target-2 rules: 0");
+        assert_eq!(game.recommend().len(), 4);
+        game.guess(0, Side::Right, Guess::Lower, card("c13"))
+            .unwrap();
+        assert_eq!(game.score(), Some(1));
+        assert!(game.recommend().iter().all(|r| r.row == 1));
+        assert_eq!(
+            game.guess(0, Side::Left, Guess::Lower, card("c12")),
+            Err(Error::GuessNotAllowed {
+                row: 0,
+                side: Side::Left
+            })
+        );
+        game.apply(0, Command::AddLeft, Some(card("c12"))).unwrap();
+        assert_eq!(game.table().rows[0].len(), 3);
+    }
+
+    #[test]
+    fn game_rejects_invalid_commands() {
+        let mut game = game();
+        assert_eq!(
+            game.apply(2, Command::AddLeft, Some(card("c13"))),
+            Err(Error::RowNotFound(2))
+        );
+        assert_eq!(
+            game.apply(0, Command::Collapse, None),
+            Err(Error::CardRequired)
+        );
+        assert_eq!(
+            game.apply(0, Command::AddLeft, Some(card("b11"))),
+            Err(Error::CardAlreadyRemoved(card("b11")))
+        );
+        assert_eq!(
+            game.apply(0, Command::RemoveLeft, None),
+            Err(Error::RowEmpty(0))
+        );
+        assert_eq!(
+            game.apply(1, Command::RemoveRight, None),
+            Err(Error::RowEmpty(1))
+        );
+        assert!(game.history().is_empty());
+        assert_eq!(game.deck().size(), 14);
+    }
+
+    #[test]
+    fn game_can_undo_and_redo() {
+        let mut game = game();
+        let mut snapshots = vec![snapshot(&game)];
+        game.apply(0, Command::AddRight, Some(card("c13"))).unwrap();
+        snapshots.push(snapshot(&game));
+        game.apply(0, Command::AddLeft, Some(card("d12"))).unwrap();
+        snapshots.push(snapshot(&game));
+        game.apply(0, Command::Collapse, Some(card("c12"))).unwrap();
+        snapshots.push(snapshot(&game));
+        game.apply(1, Command::AddRight, Some(card("a13"))).unwrap();
+        snapshots.push(snapshot(&game));
+        game.apply(1, Command::RemoveRight, None).unwrap();
+        snapshots.push(snapshot(&game));
+        for i in (0..5).rev() {
+            game.undo().unwrap();
+            assert_eq!(snapshot(&game), snapshots[i]);
+        }
+        assert_eq!(game.undo(), Err(Error::NothingToUndo));
+        for snap in snapshots.iter().skip(1) {
+            game.redo().unwrap();
+            assert_eq!(&snapshot(&game), snap);
+        }
+        assert_eq!(game.redo(), Err(Error::NothingToRedo));
+    }
+
+    #[test]
+    fn game_can_seek_history() {
+        let mut game = game();
+        let start = snapshot(&game);
+        game.apply(0, Command::AddRight, Some(card("c13"))).unwrap();
+        let middle = snapshot(&game);
+        game.apply(0, Command::Collapse, Some(card("d13"))).unwrap();
+        let end = snapshot(&game);
+        game.seek(0).unwrap();
+        assert_eq!(snapshot(&game), start);
+        game.seek(2).unwrap();
+        assert_eq!(snapshot(&game), end);
+        game.seek(1).unwrap();
+        assert_eq!(snapshot(&game), middle);
+        assert_eq!(game.seek(3), Err(Error::HistoryOutOfRange(3)));
+    }
+
+    #[test]
+    fn game_discards_undone_events_on_apply() {
+        let mut game = game();
+        game.apply(0, Command::AddRight, Some(card("c13"))).unwrap();
+        game.apply(0, Command::AddRight, Some(card("c12"))).unwrap();
+        game.undo().unwrap();
+        game.apply(1, Command::AddLeft, Some(card("c12"))).unwrap();
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(game.position(), 2);
+        assert!(!game.can_redo());
+        assert_eq!(game.history()[1].row, 1);
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, Rank};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Guess {
+    Higher,
+    Lower,
+}
+
+/// End of a row a card can be added to or compared against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    Push,
+    Lose,
+}
+
+impl fmt::Display for Guess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Guess::Higher => write!(f, "higher"),
+            Guess::Lower => write!(f, "lower"),
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Side::Left => write!(f, "left"),
+            Side::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// House rule deciding what happens when the drawn card ties the row end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TieRule {
+    /// A tie loses both bets.
+    #[default]
+    Lose,
+    /// A tie returns the stake, neither winning nor losing.
+    Push,
+    /// "Higher" is played as "higher or equal", so a tie wins it.
+    HigherOrEqual,
+}
+
+impl TieRule {
+    pub fn outcome(&self, guess: Guess, base: Rank, drawn: Rank) -> Outcome {
+        match (drawn.cmp(&base), guess) {
+            (Ordering::Greater, Guess::Higher) | (Ordering::Less, Guess::Lower) => Outcome::Win,
+            (Ordering::Equal, guess) => match self {
+                TieRule::Lose => Outcome::Lose,
+                TieRule::Push => Outcome::Push,
+                TieRule::HigherOrEqual if guess == Guess::Higher => Outcome::Win,
+                TieRule::HigherOrEqual => Outcome::Lose,
+            },
+            _ => Outcome::Lose,
+        }
+    }
+}
+
+impl FromStr for TieRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<TieRule, Error> {
+        match s {
+            "lose" => Ok(TieRule::Lose),
+            "push" => Ok(TieRule::Push),
+            "higher-or-equal" => Ok(TieRule::HigherOrEqual),
+            _ => Err(Error::UnknownTieRule(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tie_rule_decides_ties() {
+        let (low, high) = (Rank::new(5).unwrap(), Rank::KING);
+        for rule in [TieRule::Lose, TieRule::Push, TieRule::HigherOrEqual] {
+            assert_eq!(rule.outcome(Guess::Higher, low, high), Outcome::Win);
+            assert_eq!(rule.outcome(Guess::Lower, low, high), Outcome::Lose);
+            assert_eq!(rule.outcome(Guess::Lower, high, low), Outcome::Win);
+        }
+        assert_eq!(
+            TieRule::Lose.outcome(Guess::Higher, low, low),
+            Outcome::Lose
+        );
+        assert_eq!(TieRule::Push.outcome(Guess::Lower, low, low), Outcome::Push);
+        assert_eq!(
+            TieRule::HigherOrEqual.outcome(Guess::Higher, low, low),
+            Outcome::Win
+        );
+        assert_eq!(
+            TieRule::HigherOrEqual.outcome(Guess::Lower, low, low),
+            Outcome::Lose
+        );
+    }
+
+    #[test]
+    fn tie_rule_can_be_parsed() {
+        assert_eq!("lose".parse(), Ok(TieRule::Lose));
+        assert_eq!("push".parse(), Ok(TieRule::Push));
+        assert_eq!("higher-or-equal".parse(), Ok(TieRule::HigherOrEqual));
+        assert_eq!(
+            "draw".parse::<TieRule>(),
+            Err(Error::UnknownTieRule(String::from("draw")))
+        );
+    }
+}
@@ -1,152 +1,462 @@
-use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
-use std::io::{Error, ErrorKind};
-use termion::color::LightBlack;
+use std::io::{self, Write};
+use std::str::FromStr;
 
 extern crate termion;
-use termion::{clear, color, cursor};
+use termion::{clear, color, style};
+use theme::Color;
 
+mod card;
+mod error;
+mod game;
+mod guess;
+mod picker;
+pub mod practice;
+mod ratio;
+mod recommend;
+pub mod render;
+pub mod rules;
+mod save;
+pub mod script;
+mod shoe;
+pub mod sim;
+pub mod solver;
+pub mod theme;
+
+pub use card::{Card, Rank, Suit};
+pub use error::Error;
+pub use game::{Event, Game};
+pub use guess::{Guess, Outcome, Side, TieRule};
+pub use picker::Picker;
+pub use ratio::Ratio;
+pub use recommend::{recommend, Recommendation};
+pub use rules::Rules;
+pub use shoe::Shoe;
+pub use theme::Theme;
+
+/// Options for printing cards, odds and tables.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Style {
+    /// Use terminal colors; selection and suits fall back to plain
+    /// attributes when disabled.
+    pub color: bool,
+    /// Use escape sequences at all. Without them there are no colors either,
+    /// and the selected row and best move are marked with `>` and `*`.
+    pub escapes: bool,
+    /// Replace suit symbols and arrows with ASCII letters.
+    pub ascii: bool,
+    pub theme: Theme,
+}
+
+impl Style {
+    /// Text without any escape sequences, e.g. for files and screen readers.
+    pub fn plain() -> Style {
+        Style {
+            color: false,
+            escapes: false,
+            ascii: false,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn colored(&self) -> bool {
+        self.color && self.escapes
+    }
+
+    /// The suit's symbol, or its letter in ASCII mode.
+    pub fn suit(&self, suit: Suit) -> &'static str {
+        match self.ascii {
+            true => suit.ascii(),
+            false => suit.symbol(),
+        }
+    }
+
+    /// The arrows marking the higher, equal and lower odds.
+    pub fn arrows(&self) -> [&'static str; 3] {
+        match self.ascii {
+            true => ["^", "<>", "v"],
+            false => ["▲", "◀▶", "▼"],
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style {
+            color: true,
+            escapes: true,
+            ascii: false,
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// Change to a row; parsed from the short forms `c`, `al`, `ar`, `dl`, `dr`
+/// and `x`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Command {
     Collapse,
     AddLeft,
     AddRight,
     RemoveLeft,
     RemoveRight,
+    /// Draws a card without changing the row, as after a pushed tie.
+    Discard,
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Command, Error> {
+        match s {
+            "c" => Ok(Command::Collapse),
+            "al" => Ok(Command::AddLeft),
+            "ar" => Ok(Command::AddRight),
+            "dl" => Ok(Command::RemoveLeft),
+            "dr" => Ok(Command::RemoveRight),
+            "x" => Ok(Command::Discard),
+            _ => Err(Error::UnknownCommand(s.to_string())),
+        }
+    }
+}
+
+impl Command {
+    /// The change a guess on the `side` end of a row makes with the drawn
+    /// card: a win adds the card at that end, a push discards it and a loss
+    /// collapses the row onto it.
+    pub fn from_outcome(outcome: Outcome, side: Side) -> Command {
+        match (outcome, side) {
+            (Outcome::Win, Side::Left) => Command::AddLeft,
+            (Outcome::Win, Side::Right) => Command::AddRight,
+            (Outcome::Push, _) => Command::Discard,
+            (Outcome::Lose, _) => Command::Collapse,
+        }
+    }
+}
+
+/// Composition of a deck or multi-deck shoe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckSpec {
+    decks: usize,
+    lowest: Rank,
+    highest: Rank,
+    suits: Vec<Suit>,
+    jokers: usize,
+}
+
+impl DeckSpec {
+    /// A single deck of `size` cards, counting down from the ace.
+    pub fn from_size(size: usize) -> Result<DeckSpec, Error> {
+        if size == 0 || size > 52 || !size.is_multiple_of(4) {
+            return Err(Error::InvalidDeckSize(size));
+        }
+        let lowest = Rank::new(Rank::ACE.value() + 1 - (size / 4) as u8).unwrap();
+        Ok(DeckSpec::new().lowest(lowest))
+    }
+
+    /// A single standard 52-card deck without jokers.
+    pub fn new() -> DeckSpec {
+        DeckSpec {
+            decks: 1,
+            lowest: Rank::TWO,
+            highest: Rank::ACE,
+            suits: Suit::ALL.to_vec(),
+            jokers: 0,
+        }
+    }
+
+    /// Number of combined decks, i.e. copies of every regular card.
+    pub fn decks(mut self, decks: usize) -> DeckSpec {
+        self.decks = decks;
+        self
+    }
+
+    pub fn lowest(mut self, rank: Rank) -> DeckSpec {
+        self.lowest = rank;
+        self
+    }
+
+    pub fn highest(mut self, rank: Rank) -> DeckSpec {
+        self.highest = rank;
+        self
+    }
+
+    pub fn suits(mut self, suits: &[Suit]) -> DeckSpec {
+        self.suits = suits.to_vec();
+        self
+    }
+
+    /// Total number of jokers in the shoe.
+    pub fn jokers(mut self, jokers: usize) -> DeckSpec {
+        self.jokers = jokers;
+        self
+    }
+
+    pub fn build(&self) -> Result<Deck, Error> {
+        Deck::from_spec(self)
+    }
+
+    /// Number of copies of the card in a full shoe.
+    pub fn copies(&self, card: &Card) -> usize {
+        if card.is_joker() {
+            return self.jokers;
+        }
+        if card.rank < self.lowest || card.rank > self.highest || !self.suits.contains(&card.suit) {
+            return 0;
+        }
+        self.decks
+    }
+
+    /// Every distinct card of the composition, regardless of copies.
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards = Vec::new();
+        if self.decks > 0 {
+            for value in self.lowest.value()..=self.highest.value() {
+                let rank = Rank::new(value).unwrap();
+                for suit in self.suits.iter() {
+                    cards.push(Card::new(*suit, rank));
+                }
+            }
+        }
+        if self.jokers > 0 {
+            cards.push(Card::joker());
+        }
+        cards
+    }
+
+    pub fn size(&self) -> usize {
+        self.cards().iter().map(|c| self.copies(c)).sum()
+    }
+
+    fn is_valid(&self) -> bool {
+        let mut suits = self.suits.clone();
+        suits.sort();
+        suits.dedup();
+        self.lowest <= self.highest
+            && suits.len() == self.suits.len()
+            && !self.suits.contains(&Suit::Joker)
+            && self.size() > 0
+    }
+}
+
+impl Default for DeckSpec {
+    fn default() -> DeckSpec {
+        DeckSpec::new()
+    }
+}
+
+/// Cards winning a "higher" bet, tying and winning a "lower" bet against
+/// a card, out of `total` cards left in the deck.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Odds {
+    pub higher: usize,
+    pub equal: usize,
+    pub lower: usize,
+    pub total: usize,
+}
+
+impl Odds {
+    pub fn wins(&self, guess: Guess) -> usize {
+        match guess {
+            Guess::Higher => self.higher,
+            Guess::Lower => self.lower,
+        }
+    }
+
+    /// Exact chance of drawing one of `count` cards, zero for an empty deck.
+    pub fn chance(&self, count: usize) -> Ratio {
+        if self.total == 0 {
+            return Ratio::ZERO;
+        }
+        Ratio::new(count as i128, self.total as i128)
+    }
 }
 
 pub struct Deck {
+    spec: DeckSpec,
+    tie_rule: TieRule,
     size: usize,
-    cards: HashMap<String, bool>,
-    values: HashMap<usize, usize>,
+    cards: HashMap<Card, usize>,
+    values: HashMap<Rank, usize>,
 }
 
 impl Deck {
+    /// A single deck of `size` cards, counting down from the ace.
     pub fn new(size: usize) -> Result<Deck, Error> {
-        if size > 52 || size % 4 != 0 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Deck size must no larger than 52 and divisible by 4",
-            ));
+        DeckSpec::from_size(size)?.build()
+    }
+
+    pub fn from_spec(spec: &DeckSpec) -> Result<Deck, Error> {
+        if !spec.is_valid() {
+            return Err(Error::InvalidDeckSpec(spec.clone()));
         }
         let mut cards = HashMap::new();
         let mut values = HashMap::new();
-        let mut val = 14;
-        for _ in 0..(size / 4) {
-            values.insert(val, 4);
-            for c in ['a', 'b', 'c', 'd'] {
-                cards.insert(format!("{}{}", c, val), true);
-            }
-            val = val - 1;
+        for card in spec.cards() {
+            let copies = spec.copies(&card);
+            cards.insert(card, copies);
+            *values.entry(card.rank).or_insert(0) += copies;
         }
         Ok(Deck {
-            size,
+            spec: spec.clone(),
+            tie_rule: TieRule::default(),
+            size: spec.size(),
             cards,
             values,
         })
     }
 
-    pub fn is_card(&self, card: &String) -> bool {
-        let re: Regex = Regex::new(r"^[abcd]\d{1,2}$").unwrap();
-        if !re.is_match(card) {
-            return false;
-        }
-        let value = match Deck::parse_value(card) {
-            Ok(value) => value,
-            Err(_) => return false,
-        };
-        if value > 14 || value < (14 - self.cards.len() / 4) + 1 {
-            return false;
-        }
-        true
+    pub fn spec(&self) -> &DeckSpec {
+        &self.spec
+    }
+
+    pub fn tie_rule(&self) -> TieRule {
+        self.tie_rule
+    }
+
+    pub fn set_tie_rule(&mut self, tie_rule: TieRule) {
+        self.tie_rule = tie_rule;
     }
 
-    pub fn has_card(&self, card: &String) -> bool {
+    pub fn is_card(&self, card: &Card) -> bool {
+        self.cards.contains_key(card)
+    }
+
+    pub fn has_card(&self, card: &Card) -> bool {
+        self.count(card) > 0
+    }
+
+    /// Number of cards left in the deck.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of copies of the card left in the deck.
+    pub fn count(&self, card: &Card) -> usize {
         match self.cards.get(card) {
-            Some(card) => *card,
-            None => false,
+            Some(count) => *count,
+            None => 0,
         }
     }
 
-    pub fn add(&mut self, card: String) -> Result<(), Error> {
-        if !self.is_card(&card) {
-            return Err(Error::new(ErrorKind::InvalidInput, "Card not in deck"));
-        }
-        let value = match Deck::parse_value(&card) {
-            Ok(value) => value,
-            Err(e) => return Err(e),
-        };
-        self.cards.insert(card, true);
-        let count = match self.values.get_mut(&value) {
-            Some(count) => count,
-            None => {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "Card value not in deck",
-                ))
-            }
-        };
-        *count = *count + 1;
-        self.size = self.size + 1;
-        Ok(())
+    /// Every card left in the deck, one entry per copy, in the order of
+    /// [`DeckSpec::cards`].
+    pub fn cards(&self) -> Vec<Card> {
+        self.spec
+            .cards()
+            .into_iter()
+            .flat_map(|card| std::iter::repeat_n(card, self.count(&card)))
+            .collect()
     }
 
-    pub fn remove(&mut self, card: &String) -> Result<(), Error> {
-        let value = match Deck::parse_value(card) {
-            Ok(value) => value,
-            Err(e) => return Err(e),
-        };
-        self.cards.insert(card.clone(), false);
-        let count = match self.values.get_mut(&value) {
-            Some(count) => count,
-            None => return Err(Error::new(ErrorKind::InvalidInput, "Card not in deck")),
-        };
-        *count = *count - 1;
-        self.size = self.size - 1;
-        Ok(())
+    /// Cards left per rank, lowest rank first.
+    pub fn ranks(&self) -> Vec<(Rank, usize)> {
+        let mut ranks: Vec<(Rank, usize)> = self.values.iter().map(|(r, n)| (*r, *n)).collect();
+        ranks.sort();
+        ranks
     }
 
-    pub fn calc(&self, card: &String) -> Result<(f32, f32, f32), Error> {
-        let comp_value = match Deck::parse_value(&card) {
-            Ok(comp_value) => comp_value,
-            Err(e) => return Err(e),
-        };
-        let mut higher = 0;
-        let mut equal = 0;
-        let mut lower = 0;
-        for (value, count) in self.values.iter() {
-            if *value > comp_value {
-                higher = higher + *count;
-            } else if *value == comp_value {
-                equal = equal + *count;
-            } else {
-                lower = lower + *count;
+    pub fn add(&mut self, card: Card) -> Result<(), Error> {
+        let copies = self.spec.copies(&card);
+        match self.cards.get_mut(&card) {
+            Some(count) if *count >= copies => Err(Error::CardAlreadyInDeck(card)),
+            Some(count) => {
+                *count += 1;
+                *self.values.entry(card.rank).or_insert(0) += 1;
+                self.size += 1;
+                Ok(())
             }
+            None => Err(Error::CardNotInDeck(card)),
         }
-        let chance = |n| -> f32 { n as f32 / self.size as f32 };
-        Ok((chance(higher), chance(equal), chance(lower)))
     }
 
-    pub fn format_card_chance(&self, card: &String) -> String {
-        let (higher, equal, lower) = self.calc(card).unwrap();
-        format!(
-            "{}▲ {:.2} {}◀▶ {:.2} {}▼ {:.2}{}",
-            color::Fg(color::Green),
-            higher,
-            color::Fg(color::Reset),
-            equal,
-            color::Fg(color::Blue),
-            lower,
-            color::Fg(color::Reset)
+    pub fn remove(&mut self, card: &Card) -> Result<(), Error> {
+        match self.cards.get_mut(card) {
+            Some(0) => Err(Error::CardAlreadyRemoved(*card)),
+            Some(count) => {
+                *count -= 1;
+                if let Some(count) = self.values.get_mut(&card.rank) {
+                    *count -= 1;
+                }
+                self.size -= 1;
+                Ok(())
+            }
+            None => Err(Error::CardNotInDeck(*card)),
+        }
+    }
+
+    /// Chances of winning a "higher" bet, drawing a tie and winning a
+    /// "lower" bet against the card under the deck's tie rule.
+    pub fn calc(&self, card: &Card) -> (f32, f32, f32) {
+        let odds = self.calc_exact(card);
+        (
+            odds.chance(odds.higher).to_f32(),
+            odds.chance(odds.equal).to_f32(),
+            odds.chance(odds.lower).to_f32(),
         )
     }
 
-    fn parse_value(card: &String) -> Result<usize, Error> {
-        match card[1..].parse::<usize>() {
-            Ok(card) => Ok(card),
-            Err(_) => return Err(Error::new(ErrorKind::InvalidInput, "Invalid card value")),
+    /// Exact card counts behind [`Deck::calc`].
+    pub fn calc_exact(&self, card: &Card) -> Odds {
+        Odds {
+            higher: self.count_wins(card, Guess::Higher),
+            equal: self.values.get(&card.rank).copied().unwrap_or(0),
+            lower: self.count_wins(card, Guess::Lower),
+            total: self.size,
         }
     }
+
+    /// Exact chance of winning the guess against the card under the deck's
+    /// tie rule.
+    pub fn chance(&self, card: &Card, guess: Guess) -> Ratio {
+        let odds = self.calc_exact(card);
+        odds.chance(odds.wins(guess))
+    }
+
+    /// The guess with the better chance of winning against the card.
+    pub fn recommend(&self, card: &Card) -> Guess {
+        if self.count_wins(card, Guess::Lower) > self.count_wins(card, Guess::Higher) {
+            return Guess::Lower;
+        }
+        Guess::Higher
+    }
+
+    fn count_wins(&self, card: &Card, guess: Guess) -> usize {
+        self.values
+            .iter()
+            .filter(|(rank, _)| self.tie_rule.outcome(guess, card.rank, **rank) == Outcome::Win)
+            .map(|(_, count)| *count)
+            .sum()
+    }
+
+    /// The chances of drawing a higher, equal and lower card, with the
+    /// recommended guess marked.
+    pub fn format_card_chance(&self, card: &Card, style: Style) -> String {
+        let (higher, equal, lower) = self.calc(card);
+        let [up, even, down] = style.arrows();
+        let marker = if style.ascii { "!" } else { "»" };
+        let (mark_higher, mark_lower) = match self.recommend(card) {
+            Guess::Higher => (marker, " "),
+            Guess::Lower => (" ", marker),
+        };
+        let higher_text = format!("{}{} {:.2}", mark_higher, up, higher);
+        let equal_text = format!("{} {:.2}", even, equal);
+        let lower_text = format!("{}{} {:.2}", mark_lower, down, lower);
+        if !style.colored() {
+            return format!("{} {} {}", higher_text, equal_text, lower_text);
+        }
+        let theme = style.theme;
+        format!(
+            "{} {} {}{}",
+            theme.heat(&higher_text, higher, theme.higher),
+            theme.heat(&equal_text, equal, theme.equal),
+            theme.heat(&lower_text, lower, theme.lower),
+            color::Fg(color::Reset)
+        )
+    }
 }
 
 pub struct Table {
@@ -154,52 +464,135 @@ pub struct Table {
 }
 
 impl Table {
-    pub fn new(row_count: usize, cards: Vec<String>) -> Result<Table, Error> {
+    pub fn new(row_count: usize, cards: Vec<Card>) -> Result<Table, Error> {
+        if row_count == 0 {
+            return Err(Error::NoRows);
+        }
         if row_count != cards.len() {
-            return Err(Error::new(ErrorKind::InvalidInput, "Invalid card amount"));
+            return Err(Error::RowCountMismatch {
+                rows: row_count,
+                cards: cards.len(),
+            });
+        }
+        let rows = cards.into_iter().map(Row::new).collect();
+        Ok(Table { rows })
+    }
+
+    /// Builds a table from the full contents of each row.
+    pub fn from_rows(rows: Vec<Vec<Card>>) -> Result<Table, Error> {
+        if rows.is_empty() {
+            return Err(Error::NoRows);
         }
-        let mut rows = Vec::new();
-        for i in 0..row_count {
-            let card = cards.get(i).unwrap().clone();
-            rows.push(Row::new(card));
+        if let Some(row) = rows.iter().position(|cards| cards.is_empty()) {
+            return Err(Error::RowEmpty(row));
         }
+        let rows = rows.into_iter().map(|cards| Row { cards }).collect();
         Ok(Table { rows })
     }
 
     pub fn has_row(&self, row_num: usize) -> bool {
-        match self.rows.get(row_num) {
-            Some(_) => true,
-            None => false,
+        self.rows.get(row_num).is_some()
+    }
+
+    /// Writes the table laid out for `width` columns, one line per `\n`.
+    pub fn render<W: Write>(
+        &self,
+        out: &mut W,
+        deck: &Deck,
+        best: Option<Recommendation>,
+        sel_row: usize,
+        style: Style,
+        width: usize,
+    ) -> io::Result<()> {
+        for line in self.lines(deck, best, sel_row, style, width) {
+            writeln!(out, "{}", line)?;
         }
+        Ok(())
     }
 
-    pub fn print(&self, deck: &Deck, sel_row: usize) {
-        let mut row_num: usize = 0;
-        print!("{}", cursor::Goto(1, 1));
-        for row in self.rows.iter() {
-            if row_num == sel_row {
-                print!("{}", color::Bg(color::LightBlack));
+    /// The table as screen lines, underlining the odds of `best`, the best
+    /// next move under the game's rules.
+    ///
+    /// Every row is followed by a blank line. Rows wider than `width` columns
+    /// are laid out over several lines: the left end's odds, the cards, and
+    /// the right end's odds. The odds of one end are never split.
+    pub fn lines(
+        &self,
+        deck: &Deck,
+        best: Option<Recommendation>,
+        sel_row: usize,
+        style: Style,
+        width: usize,
+    ) -> Vec<String> {
+        let format_end = |row_num: usize, side: Side, card: &Card| {
+            let chance = deck.format_card_chance(card, style);
+            match best {
+                Some(r) if r.row == row_num && r.side == side && style.escapes => {
+                    format!("{}{}{}", style::Underline, chance, style::NoUnderline)
+                }
+                Some(r) if r.row == row_num && r.side == side => format!("{} *", chance),
+                _ => chance,
             }
-            println!(
-                "{}{}\t---\t{}\t---\t{}{}\n{}",
-                clear::CurrentLine,
-                deck.format_card_chance(row.get_left()),
-                row,
-                deck.format_card_chance(row.get_right()),
-                color::Bg(color::Reset),
-                clear::CurrentLine,
-            );
-            row_num = row_num + 1;
+        };
+        let mut lines = Vec::new();
+        for (row_num, row) in self.rows.iter().enumerate() {
+            let (select, deselect) = match (row_num == sel_row, style.colored()) {
+                (false, _) if !style.escapes => (String::from("  "), String::new()),
+                (true, _) if !style.escapes => (String::from("> "), String::new()),
+                (false, _) => (String::new(), String::new()),
+                (true, true) => (
+                    style.theme.selected.bg(),
+                    color::Bg(color::Reset).to_string(),
+                ),
+                (true, false) => (style::Invert.to_string(), style::NoInvert.to_string()),
+            };
+            // Columns left after the selection marker.
+            let width = width.saturating_sub(render::width(&select));
+            // An end's odds follow its label, or go below it if too wide.
+            let labelled = |label: &str, odds: String| {
+                let line = format!("{:<6}{}", label, odds);
+                match render::width(&line) <= width {
+                    true => vec![line],
+                    false => vec![String::from(label), odds],
+                }
+            };
+            let left = format_end(row_num, Side::Left, row.get_left());
+            let right = format_end(row_num, Side::Right, row.get_right());
+            let line = format!("{}  ---  {}  ---  {}", left, row.format(style), right);
+            let row_lines = if render::width(&line) <= width {
+                vec![line]
+            } else {
+                // Cards are 6 columns wide and separated by a space.
+                let per_line = (width.saturating_add(1) / 7).max(1);
+                let mut wrapped = labelled("left", left);
+                for cards in row.cards.chunks(per_line) {
+                    let cards: Vec<String> =
+                        cards.iter().map(|c| Row::format_card(c, style)).collect();
+                    wrapped.push(cards.join(" "));
+                }
+                wrapped.extend(labelled("right", right));
+                wrapped
+            };
+            for line in row_lines {
+                // Clearing after selecting fills the whole line with the background.
+                let clear = match style.escapes {
+                    true => clear::CurrentLine.to_string(),
+                    false => String::new(),
+                };
+                lines.push(format!("{}{}{}{}", select, clear, line, deselect));
+            }
+            lines.push(String::new());
         }
+        lines
     }
 }
 
 pub struct Row {
-    cards: Vec<String>,
+    cards: Vec<Card>,
 }
 
 impl Row {
-    fn new(card: String) -> Row {
+    fn new(card: Card) -> Row {
         Row { cards: vec![card] }
     }
 
@@ -207,75 +600,117 @@ impl Row {
         self.cards.len()
     }
 
-    pub fn get_left(&self) -> &String {
-        return self.cards.get(0).unwrap();
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn get(&self, side: Side) -> &Card {
+        match side {
+            Side::Left => self.get_left(),
+            Side::Right => self.get_right(),
+        }
+    }
+
+    pub fn get_left(&self) -> &Card {
+        self.cards.first().unwrap()
     }
 
-    pub fn add_left(&mut self, card: String) {
+    pub fn add_left(&mut self, card: Card) {
         self.cards.insert(0, card);
     }
 
-    pub fn remove_left(&mut self, deck: &mut Deck) {
-        let card = self.cards.remove(0);
-        deck.add(card).unwrap();
+    /// Returns the leftmost card to the deck. The row must keep at least one
+    /// card.
+    pub fn remove_left(&mut self, deck: &mut Deck) -> Result<(), Error> {
+        deck.add(self.cards[0])?;
+        self.cards.remove(0);
+        Ok(())
     }
 
-    pub fn get_right(&self) -> &String {
-        return self.cards.get(self.cards.len() - 1).unwrap();
+    pub fn get_right(&self) -> &Card {
+        self.cards.last().unwrap()
     }
 
-    pub fn add_right(&mut self, card: String) {
+    pub fn add_right(&mut self, card: Card) {
         self.cards.push(card)
     }
 
-    pub fn remove_right(&mut self, deck: &mut Deck) {
-        let card = self.cards.pop().unwrap();
-        deck.add(card).unwrap();
+    /// Returns the rightmost card to the deck. The row must keep at least
+    /// one card.
+    pub fn remove_right(&mut self, deck: &mut Deck) -> Result<(), Error> {
+        deck.add(*self.get_right())?;
+        self.cards.pop();
+        Ok(())
     }
 
-    pub fn collapse(&mut self, card: String, deck: &mut Deck) {
+    pub fn collapse(&mut self, card: Card, deck: &mut Deck) -> Result<(), Error> {
+        if let Some(c) = self.cards.iter().find(|c| !deck.is_card(c)) {
+            return Err(Error::CardNotInDeck(*c));
+        }
+        for c in self.cards.iter() {
+            let returned = self.cards.iter().filter(|other| *other == c).count();
+            if deck.count(c) + returned > deck.spec.copies(c) {
+                return Err(Error::CardAlreadyInDeck(*c));
+            }
+        }
         for c in self.cards.drain(..) {
-            deck.add(c).unwrap();
+            deck.add(c)?;
         }
         self.cards.push(card);
+        Ok(())
     }
 
-    fn format_card(card: &String) -> String {
-        let mut red = false;
-        let (suit, value) = card.split_at(1);
-        let suit = match suit {
-            "a" => "♣",
-            "b" => "♠",
-            "c" => {
-                red = true;
-                "♥"
-            }
-            "d" => {
-                red = true;
-                "♦"
-            }
-            _ => "",
-        };
-        let value = match value {
-            "14" => "A",
-            "13" => "K",
-            "12" => "Q",
-            "11" => "J",
-            value => value,
-        };
-        let padding = match value.len() {
+    /// Changes the row by the outcome of a guess on the `side` end, as
+    /// [`Command::from_outcome`]. The drawn card must already be removed
+    /// from the deck.
+    pub fn resolve(
+        &mut self,
+        side: Side,
+        outcome: Outcome,
+        card: Card,
+        deck: &mut Deck,
+    ) -> Result<Command, Error> {
+        let command = Command::from_outcome(outcome, side);
+        match command {
+            Command::AddLeft => self.add_left(card),
+            Command::AddRight => self.add_right(card),
+            Command::Collapse => self.collapse(card, deck)?,
+            _ => {}
+        }
+        Ok(command)
+    }
+
+    /// The row's cards, separated by spaces.
+    pub fn format(&self, style: Style) -> String {
+        let cards: Vec<String> = self
+            .cards
+            .iter()
+            .map(|c| Row::format_card(c, style))
+            .collect();
+        cards.join(" ")
+    }
+
+    /// A single card as shown in rows, e.g. `[♥  Q]`.
+    pub fn format_card(card: &Card, style: Style) -> String {
+        let label = card.rank.label();
+        let padding = match label.len() {
             1 => " ",
             _ => "",
         };
-        if !red {
-            return format!("[{} {}{}]", suit, padding, value);
+        let suit_color = style.theme.suit(card.suit);
+        if suit_color == Color::Default || !style.colored() {
+            return format!("[{} {}{}]", style.suit(card.suit), padding, label);
         }
         format!(
             "{}[{} {}{}]{}",
-            color::Fg(color::Red),
-            suit,
+            suit_color.fg(),
+            style.suit(card.suit),
             padding,
-            value,
+            label,
             color::Fg(color::Reset)
         )
     }
@@ -283,121 +718,368 @@ impl Row {
 
 impl fmt::Display for Row {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut cards = self.cards.iter();
-        let card = cards.next();
-        let mut fmt_string = Row::format_card(card.unwrap());
-        loop {
-            match cards.next() {
-                Some(c) => fmt_string = format!("{} {}", fmt_string, Row::format_card(c)),
-                None => break,
-            };
+        write!(f, "{}", self.format(Style::default()))
+    }
+}
+
+/// Fixtures shared by the test modules.
+#[cfg(test)]
+mod fixture {
+    use crate::{Card, Deck, Table};
+
+    pub fn card(s: &str) -> Card {
+        s.parse().unwrap()
+    }
+
+    /// A deck of `size` cards and a table with a row for each card, the
+    /// cards removed from the deck.
+    pub fn deal(size: usize, cards: &[&str]) -> (Deck, Table) {
+        let mut deck = Deck::new(size).unwrap();
+        let cards: Vec<Card> = cards.iter().map(|c| card(c)).collect();
+        for c in cards.iter() {
+            deck.remove(c).unwrap();
         }
-        write!(f, "{}", fmt_string)
+        let table = Table::new(cards.len(), cards).unwrap();
+        (deck, table)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::fixture::{card, deal};
+    use proptest::prelude::*;
 
     #[test]
     fn deck_can_be_created() {
         let deck = Deck::new(52).unwrap();
         assert_eq!(deck.size, 52);
-        let card = String::from("a5");
-        let card = deck.cards.get(&card).unwrap();
-        assert!(card);
-        assert_eq!(*deck.values.get(&7).unwrap(), 4);
+        assert!(deck.has_card(&card("a5")));
+        assert_eq!(*deck.values.get(&Rank::new(7).unwrap()).unwrap(), 4);
+        assert!(!deck.is_card(&Card::joker()));
     }
 
     #[test]
     fn deck_can_add_cards() {
         let mut deck = Deck::new(36).unwrap();
-        let card = String::from("a10");
-        deck.add(card.clone()).unwrap();
-        assert_eq!(deck.size, 37);
-        let card = deck.cards.get(&card).unwrap();
-        assert!(card);
+        let card = card("a10");
+        deck.remove(&card).unwrap();
+        deck.add(card).unwrap();
+        assert_eq!(deck.size, 36);
+        assert!(deck.has_card(&card));
     }
 
     #[test]
     fn deck_can_remove_cards() {
         let mut deck = Deck::new(36).unwrap();
-        let card = String::from("a10");
+        let card = card("a10");
         deck.remove(&card).unwrap();
         assert_eq!(deck.size, 35);
-        let card = deck.cards.get(&card).unwrap();
-        assert!(!card);
+        assert!(!deck.has_card(&card));
+    }
+
+    #[test]
+    fn deck_rejects_duplicate_add_and_remove() {
+        let mut deck = Deck::new(8).unwrap();
+        let card = card("b13");
+        assert_eq!(deck.add(card), Err(Error::CardAlreadyInDeck(card)));
+        assert_eq!(deck.size, 8);
+        deck.remove(&card).unwrap();
+        assert_eq!(deck.remove(&card), Err(Error::CardAlreadyRemoved(card)));
+        assert_eq!(deck.size, 7);
+        assert_eq!(*deck.values.get(&card.rank).unwrap(), 3);
+    }
+
+    fn assert_consistent(deck: &Deck) {
+        assert_eq!(deck.size, deck.cards.values().sum::<usize>());
+        assert_eq!(deck.size, deck.values.values().sum::<usize>());
+        for (rank, count) in deck.values.iter() {
+            let in_deck: usize = deck
+                .cards
+                .iter()
+                .filter(|(c, _)| c.rank == *rank)
+                .map(|(_, n)| *n)
+                .sum();
+            assert_eq!(*count, in_deck);
+        }
+        for (card, count) in deck.cards.iter() {
+            assert!(*count <= deck.spec.copies(card));
+        }
+    }
+
+    fn check_consistency(deck: &mut Deck, ops: Vec<(bool, usize)>) -> Result<(), TestCaseError> {
+        let all = deck.spec.cards();
+        let mut model: HashMap<Card, usize> = all.iter().map(|c| (*c, deck.count(c))).collect();
+        for (add, i) in ops {
+            let card = all[i % all.len()];
+            let count = model.get_mut(&card).unwrap();
+            if add {
+                let ok = *count < deck.spec.copies(&card);
+                prop_assert_eq!(deck.add(card).is_ok(), ok);
+                if ok {
+                    *count += 1;
+                }
+            } else {
+                let ok = *count > 0;
+                prop_assert_eq!(deck.remove(&card).is_ok(), ok);
+                if ok {
+                    *count -= 1;
+                }
+            }
+            assert_consistent(deck);
+            prop_assert_eq!(deck.size, model.values().sum::<usize>());
+            prop_assert_eq!(deck.count(&card), model[&card]);
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn deck_stays_consistent(ops in prop::collection::vec((any::<bool>(), 0usize..52), 0..200)) {
+            let mut deck = Deck::new(52).unwrap();
+            check_consistency(&mut deck, ops)?;
+        }
+
+        #[test]
+        fn shoe_stays_consistent(ops in prop::collection::vec((any::<bool>(), 0usize..100), 0..300)) {
+            let mut deck = DeckSpec::new().decks(2).jokers(3).build().unwrap();
+            check_consistency(&mut deck, ops)?;
+        }
+    }
+
+    #[test]
+    fn deck_spec_defaults_to_standard_deck() {
+        let deck = DeckSpec::new().build().unwrap();
+        assert_eq!(deck.size, 52);
+        assert_eq!(Deck::new(52).unwrap().spec(), &DeckSpec::new());
+    }
+
+    #[test]
+    fn deck_spec_builds_piquet_deck() {
+        let deck = DeckSpec::new()
+            .lowest(Rank::new(7).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(deck.size, 32);
+        assert!(deck.has_card(&card("a7")));
+        assert!(!deck.is_card(&card("a6")));
+    }
+
+    #[test]
+    fn deck_spec_builds_custom_suits_and_ranks() {
+        let deck = DeckSpec::new()
+            .suits(&[Suit::Hearts, Suit::Spades])
+            .lowest(Rank::new(9).unwrap())
+            .highest(Rank::KING)
+            .build()
+            .unwrap();
+        assert_eq!(deck.size, 10);
+        assert!(deck.is_card(&card("c9")));
+        assert!(!deck.is_card(&card("a9")));
+        assert!(!deck.is_card(&card("b14")));
+    }
+
+    #[test]
+    fn deck_spec_rejects_invalid_compositions() {
+        let specs = [
+            DeckSpec::new().decks(0),
+            DeckSpec::new().lowest(Rank::ACE).highest(Rank::KING),
+            DeckSpec::new().suits(&[]),
+            DeckSpec::new().suits(&[Suit::Clubs, Suit::Clubs]),
+            DeckSpec::new().suits(&[Suit::Joker]),
+        ];
+        for spec in specs {
+            assert_eq!(spec.build().err(), Some(Error::InvalidDeckSpec(spec)));
+        }
+        assert!(DeckSpec::new().decks(0).jokers(2).build().is_ok());
+    }
+
+    #[test]
+    fn shoe_tracks_multiple_copies() {
+        let mut deck = DeckSpec::new().decks(2).jokers(2).build().unwrap();
+        assert_eq!(deck.size, 106);
+        let card = card("c12");
+        assert_eq!(deck.count(&card), 2);
+        deck.remove(&card).unwrap();
+        deck.remove(&card).unwrap();
+        assert_eq!(deck.remove(&card), Err(Error::CardAlreadyRemoved(card)));
+        deck.add(card).unwrap();
+        deck.add(card).unwrap();
+        assert_eq!(deck.add(card), Err(Error::CardAlreadyInDeck(card)));
+        assert_eq!(deck.count(&Card::joker()), 2);
+    }
+
+    #[test]
+    fn shoe_calculates_chance_with_eight_copies() {
+        let mut deck = DeckSpec::new().decks(2).lowest(Rank::KING).build().unwrap();
+        let card = card("a14");
+        deck.remove(&card).unwrap();
+        let odds = deck.calc_exact(&card);
+        assert_eq!(odds.chance(odds.higher), Ratio::ZERO);
+        assert_eq!(odds.chance(odds.equal), Ratio::new(7, 15));
+        assert_eq!(odds.chance(odds.lower), Ratio::new(8, 15));
+    }
+
+    #[test]
+    fn jokers_count_as_highest_rank() {
+        let mut deck = DeckSpec::new().lowest(Rank::ACE).jokers(2).build().unwrap();
+        let card = card("a14");
+        deck.remove(&card).unwrap();
+        let odds = deck.calc_exact(&card);
+        assert_eq!(odds.chance(odds.higher), Ratio::new(2, 5));
+        assert_eq!(odds.chance(odds.equal), Ratio::new(3, 5));
+        assert_eq!(odds.chance(odds.lower), Ratio::ZERO);
+    }
+
+    #[test]
+    fn row_rejects_collapse_of_cards_still_in_deck() {
+        let mut deck = Deck::new(8).unwrap();
+        let mut row = Row::new(card("a14"));
+        assert_eq!(
+            row.collapse(card("b14"), &mut deck),
+            Err(Error::CardAlreadyInDeck(card("a14")))
+        );
+        assert_eq!(row.cards, vec![card("a14")]);
+        assert_consistent(&deck);
     }
 
     #[test]
     fn deck_can_calculate_chance() {
         let mut deck = Deck::new(8).unwrap();
-        let card = String::from("a14");
+        let card = card("a14");
         deck.remove(&card).unwrap();
-        let (higher, equal, lower) = deck.calc(&card).unwrap();
+        let (higher, equal, lower) = deck.calc(&card);
         assert_eq!(higher, 0.0);
         assert_eq!(equal, 3.0 / 7.0);
         assert_eq!(lower, 4.0 / 7.0);
     }
 
+    #[test]
+    fn deck_calculates_chance_under_tie_rules() {
+        let mut deck = Deck::new(12).unwrap();
+        let card = card("a13");
+        deck.remove(&card).unwrap();
+        let odds = |higher| Odds {
+            higher,
+            equal: 3,
+            lower: 4,
+            total: 11,
+        };
+        assert_eq!(deck.calc_exact(&card), odds(4));
+        deck.set_tie_rule(TieRule::Push);
+        assert_eq!(deck.calc_exact(&card), odds(4));
+        deck.set_tie_rule(TieRule::HigherOrEqual);
+        assert_eq!(deck.calc_exact(&card), odds(7));
+        assert_eq!(deck.chance(&card, Guess::Higher), Ratio::new(7, 11));
+        assert_eq!(deck.chance(&card, Guess::Lower), Ratio::new(4, 11));
+    }
+
+    #[test]
+    fn deck_recommends_better_guess() {
+        let mut deck = Deck::new(12).unwrap();
+        let card = card("a13");
+        deck.remove(&card).unwrap();
+        deck.remove(&self::card("b14")).unwrap();
+        assert_eq!(deck.recommend(&card), Guess::Lower);
+        deck.set_tie_rule(TieRule::HigherOrEqual);
+        assert_eq!(deck.recommend(&card), Guess::Higher);
+        assert_eq!(deck.recommend(&self::card("a12")), Guess::Higher);
+    }
+
+    #[test]
+    fn deck_can_calculate_exact_odds() {
+        let mut deck = DeckSpec::new().decks(2).lowest(Rank::KING).build().unwrap();
+        let card = card("a13");
+        deck.remove(&card).unwrap();
+        let odds = deck.calc_exact(&card);
+        assert_eq!(
+            odds,
+            Odds {
+                higher: 8,
+                equal: 7,
+                lower: 0,
+                total: 15
+            }
+        );
+        assert_eq!(odds.chance(odds.higher), Ratio::new(8, 15));
+        assert_eq!(odds.chance(odds.wins(Guess::Lower)), Ratio::ZERO);
+        deck.set_tie_rule(TieRule::HigherOrEqual);
+        assert_eq!(deck.calc_exact(&card).higher, 15);
+    }
+
+    #[test]
+    fn empty_deck_has_no_chance() {
+        let mut deck = DeckSpec::new().lowest(Rank::ACE).build().unwrap();
+        for suit in Suit::ALL {
+            deck.remove(&Card::new(suit, Rank::ACE)).unwrap();
+        }
+        assert_eq!(deck.calc(&card("a14")), (0.0, 0.0, 0.0));
+    }
+
     #[test]
     fn deck_can_format_card_chance() {
         let mut deck = Deck::new(8).unwrap();
-        let card = String::from("a14");
+        let card = card("a14");
         deck.remove(&card).unwrap();
-        let (higher, equal, lower) = deck.calc(&card).unwrap();
         assert_eq!(
-            deck.format_card_chance(&card),
+            deck.format_card_chance(&card, Style::default()),
             format!(
-                "{}▲ {:.2} {}◀▶ {:.2} {}▼ {:.2}{}",
-                color::Fg(color::Green),
-                higher,
+                "{} ▲ 0.00 {}◀▶ 0.43 {}»▼ 0.57{}",
+                color::Fg(color::Red),
                 color::Fg(color::Reset),
-                equal,
                 color::Fg(color::Blue),
-                lower,
                 color::Fg(color::Reset)
             )
-        )
+        );
+        let mut deck = Deck::new(16).unwrap();
+        let card = self::card("a11");
+        deck.remove(&card).unwrap();
+        assert_eq!(
+            deck.format_card_chance(&card, Style::default()),
+            format!(
+                "{}{}»▲ 0.80{} {}◀▶ 0.20 {} ▼ 0.00{}",
+                color::Fg(color::Green),
+                style::Bold,
+                style::NoFaint,
+                color::Fg(color::Red),
+                color::Fg(color::Red),
+                color::Fg(color::Reset)
+            )
+        );
+        assert_eq!(
+            deck.format_card_chance(&card, Style::plain()),
+            "»▲ 0.80 ◀▶ 0.20  ▼ 0.00"
+        );
     }
 
     #[test]
-    fn deck_can_parse_card_values() {
-        let cards = [String::from("a1"), String::from("b14"), String::from("bb")];
-        assert_eq!(Deck::parse_value(&cards[0]).unwrap(), 1);
-        assert_eq!(Deck::parse_value(&cards[1]).unwrap(), 14);
-        let c3 = match Deck::parse_value(&cards[2]) {
-            Ok(_) => false,
-            Err(_) => true,
-        };
-        assert!(c3);
+    fn deck_can_check_card_validity() {
+        let deck = Deck::new(8).unwrap();
+        assert!(deck.is_card(&card("a14")));
+        assert!(deck.is_card(&card("d13")));
+        assert!(!deck.is_card(&card("a12")));
+        assert!(!deck.is_card(&card("b2")));
     }
 
     #[test]
-    fn deck_can_check_card_validity() {
-        let deck = Deck::new(52).unwrap();
-        let cards = [
-            String::from("a10"),
-            String::from("b20"),
-            String::from("14"),
-            String::from("!14"),
-            String::from("f10"),
-        ];
-        assert!(deck.is_card(&cards[0]));
-        for card in cards[1..].iter() {
-            assert!(!deck.is_card(&card));
-        }
+    fn deck_rejects_invalid_sizes() {
+        assert_eq!(Deck::new(53).err(), Some(Error::InvalidDeckSize(53)));
+        assert_eq!(Deck::new(10).err(), Some(Error::InvalidDeckSize(10)));
+    }
+
+    #[test]
+    fn deck_rejects_cards_outside_composition() {
+        let mut deck = Deck::new(8).unwrap();
+        let card = card("a10");
+        assert_eq!(deck.remove(&card), Err(Error::CardNotInDeck(card)));
+        assert_eq!(deck.add(card), Err(Error::CardNotInDeck(card)));
+        assert_eq!(deck.size, 8);
     }
 
     #[test]
     fn deck_can_check_card_presence() {
         let mut deck = Deck::new(8).unwrap();
-        let cards = [
-            String::from("a14"),
-            String::from("b10"),
-            String::from("c13"),
-        ];
+        let cards = [card("a14"), card("b10"), card("c13")];
         deck.remove(&cards[0]).unwrap();
         assert!(!deck.has_card(&cards[0]));
         assert!(!deck.has_card(&cards[1]));
@@ -406,110 +1088,318 @@ mod test {
 
     #[test]
     fn table_can_be_created() {
-        let cards = vec![String::from("a1"), String::from("b2"), String::from("c3")];
+        let cards = vec![card("a14"), card("b2"), card("c3")];
         let table = Table::new(3, cards.clone()).unwrap();
         for i in 0..3 {
             assert_eq!(
-                table.rows.get(i).unwrap().cards.get(0).unwrap(),
+                table.rows.get(i).unwrap().cards.first().unwrap(),
                 cards.get(i).unwrap()
             )
         }
     }
 
+    #[test]
+    fn table_rejects_mismatched_card_amount() {
+        assert_eq!(
+            Table::new(2, vec![card("a2")]).err(),
+            Some(Error::RowCountMismatch { rows: 2, cards: 1 })
+        );
+    }
+
+    #[test]
+    fn table_rejects_no_rows() {
+        assert_eq!(Table::new(0, vec![]).err(), Some(Error::NoRows));
+        assert_eq!(Table::from_rows(vec![]).err(), Some(Error::NoRows));
+    }
+
+    #[test]
+    fn table_rejects_empty_rows() {
+        let rows = vec![vec![card("a2")], vec![]];
+        assert_eq!(Table::from_rows(rows).err(), Some(Error::RowEmpty(1)));
+    }
+
     #[test]
     fn table_can_check_row_presence() {
-        let table = Table::new(1, vec![String::from("a2")]).unwrap();
+        let table = Table::new(1, vec![card("a2")]).unwrap();
         assert!(table.has_row(0));
         assert!(!table.has_row(1));
     }
 
+    fn render(table: &Table, deck: &Deck, sel_row: usize, style: Style) -> String {
+        let mut out = Vec::new();
+        table
+            .render(
+                &mut out,
+                deck,
+                recommend(deck, table).first().copied(),
+                sel_row,
+                style,
+                usize::MAX,
+            )
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn table_renders_plain() {
+        let (deck, table) = deal(8, &["a13", "c14"]);
+        let expected = [
+            format!(
+                "{}{}{}»▲ 0.50 ◀▶ 0.50  ▼ 0.00{}  ---  [♣  K]  ---  »▲ 0.50 ◀▶ 0.50  ▼ 0.00{}",
+                style::Invert,
+                clear::CurrentLine,
+                style::Underline,
+                style::NoUnderline,
+                style::NoInvert
+            ),
+            String::new(),
+            format!(
+                "{} ▲ 0.00 ◀▶ 0.50 »▼ 0.50  ---  [♥  A]  ---   ▲ 0.00 ◀▶ 0.50 »▼ 0.50",
+                clear::CurrentLine
+            ),
+            String::new(),
+        ];
+        assert_eq!(
+            render(
+                &table,
+                &deck,
+                0,
+                Style {
+                    color: false,
+                    ..Style::default()
+                }
+            ),
+            format!("{}\n", expected.join("\n"))
+        );
+    }
+
+    #[test]
+    fn table_renders_colors() {
+        let (deck, table) = deal(8, &["a13", "c14"]);
+        let rendered = render(&table, &deck, 1, Style::default());
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 4);
+        let odds = format!(
+            "{} ▲ 0.00 {}◀▶ 0.50 {}»▼ 0.50{}",
+            color::Fg(color::Red),
+            color::Fg(color::Reset),
+            color::Fg(color::Blue),
+            color::Fg(color::Reset)
+        );
+        assert_eq!(
+            lines[2],
+            format!(
+                "{}{}{}  ---  {}[♥  A]{}  ---  {}{}",
+                color::Bg(color::LightBlack),
+                clear::CurrentLine,
+                odds,
+                color::Fg(color::Red),
+                color::Fg(color::Reset),
+                odds,
+                color::Bg(color::Reset)
+            )
+        );
+        assert!(lines[0].starts_with(&format!("{}{}", clear::CurrentLine, style::Underline)));
+        assert_eq!(lines[1], "");
+    }
+
+    #[test]
+    fn table_renders_without_escapes() {
+        let (deck, table) = deal(8, &["a13", "c14"]);
+        let style = Style {
+            ascii: true,
+            ..Style::plain()
+        };
+        assert_eq!(
+            render(&table, &deck, 1, style),
+            "  !^ 0.50 <> 0.50  v 0.00 *  ---  [C  K]  ---  !^ 0.50 <> 0.50  v 0.00\n\n\
+             >  ^ 0.00 <> 0.50 !v 0.50  ---  [H  A]  ---   ^ 0.00 <> 0.50 !v 0.50\n\n"
+        );
+        let rendered = render(&table, &deck, 1, Style::plain());
+        assert!(!rendered.contains('\x1b'));
+        assert!(rendered.contains(">  ▲ 0.00 ◀▶ 0.50 »▼ 0.50  ---  [♥  A]"));
+        let lines = table.lines(&deck, None, 1, style, usize::MAX);
+        assert!(lines.iter().all(|line| !line.contains('*')));
+        let best = recommend(&deck, &table)
+            .into_iter()
+            .find(|r| r.row == 1 && r.side == Side::Right);
+        let lines = table.lines(&deck, best, 1, style, usize::MAX);
+        assert!(lines[0].ends_with("v 0.00"));
+        assert!(lines[2].ends_with("!v 0.50 *"));
+    }
+
+    #[test]
+    fn table_wraps_wide_rows() {
+        let (mut deck, mut table) = deal(52, &["a2", "b3"]);
+        for c in ["c4", "d5", "a6", "b7"].map(card) {
+            deck.remove(&c).unwrap();
+            table.rows[0].add_right(c);
+        }
+        let style = Style {
+            color: false,
+            ..Style::default()
+        };
+        let best = recommend(&deck, &table).first().copied();
+        let lines = table.lines(&deck, best, 1, style, 200);
+        assert_eq!(lines.len(), 4);
+        assert!(render::width(&lines[0]) <= 200);
+        assert!(lines[0].contains("  ---  [♣  2] [♥  4] [♦  5] [♣  6] [♠  7]  ---  "));
+        assert!(lines[2].starts_with(&style::Invert.to_string()));
+        let lines = table.lines(&deck, best, 1, style, 20);
+        assert_eq!(lines.len(), 13);
+        assert!(lines[0].ends_with("left"));
+        assert!(lines[2].ends_with("[♣  2] [♥  4] [♦  5]"));
+        assert!(lines[3].ends_with("[♣  6] [♠  7]"));
+        assert!(lines[4].ends_with("right"));
+        assert_eq!(lines[6], "");
+        assert!(lines[9].contains("[♠  3]"));
+    }
+
+    #[test]
+    fn table_wraps_at_the_marked_width() {
+        let (mut deck, mut table) = deal(52, &["a2"]);
+        for c in ["c4", "d5"].map(card) {
+            deck.remove(&c).unwrap();
+            table.rows[0].add_right(c);
+        }
+        let style = Style::plain();
+        let lines = table.lines(&deck, None, 0, style, 200);
+        let full = render::width(&lines[0]);
+        assert_eq!(table.lines(&deck, None, 0, style, full), lines);
+        let lines = table.lines(&deck, None, 0, style, full - 1);
+        assert!(lines.len() > 2);
+        assert!(lines.iter().all(|line| render::width(line) < full));
+        let lines = table.lines(&deck, None, 0, style, 26);
+        assert_eq!(lines[0], "> left");
+        assert!(lines.iter().all(|line| render::width(line) <= 26));
+    }
+
     #[test]
     fn row_can_add_cards() {
-        let card1 = String::from("a1");
-        let mut row = Row::new(card1.clone());
-        let card_left = String::from("c4");
-        let card_right = String::from("d10");
-        row.add_left(card_left.clone());
-        row.add_right(card_right.clone());
+        let card1 = card("a14");
+        let mut row = Row::new(card1);
+        let card_left = card("c4");
+        let card_right = card("d10");
+        row.add_left(card_left);
+        row.add_right(card_right);
         assert_eq!(row.cards, vec![card_left, card1, card_right]);
     }
 
     #[test]
     fn row_can_remove_cards() {
-        let cards = [String::from("a2"), String::from("b2"), String::from("c2")];
+        let cards = [card("a2"), card("b2"), card("c2")];
         let mut deck = Deck::new(52).unwrap();
         for card in cards.iter() {
             deck.remove(card).unwrap();
         }
-        let mut row = Row::new(cards[0].clone());
-        row.add_left(cards[1].clone());
-        row.add_right(cards[2].clone());
-        assert_eq!(
-            row.cards,
-            vec![cards[1].clone(), cards[0].clone(), cards[2].clone()]
-        );
-        row.remove_left(&mut deck);
-        assert_eq!(row.cards, vec![cards[0].clone(), cards[2].clone()]);
-        row.remove_right(&mut deck);
-        assert_eq!(row.cards, vec![cards[0].clone()]);
+        let mut row = Row::new(cards[0]);
+        row.add_left(cards[1]);
+        row.add_right(cards[2]);
+        assert_eq!(row.cards, vec![cards[1], cards[0], cards[2]]);
+        row.remove_left(&mut deck).unwrap();
+        assert_eq!(row.cards, vec![cards[0], cards[2]]);
+        row.remove_right(&mut deck).unwrap();
+        assert_eq!(row.cards, vec![cards[0]]);
     }
 
     #[test]
     fn row_can_collapse_into_deck() {
         let mut deck = Deck::new(16).unwrap();
-        let cards = [
-            String::from("a14"),
-            String::from("b13"),
-            String::from("c13"),
-        ];
+        let cards = [card("a14"), card("b13"), card("c13")];
         deck.remove(&cards[0]).unwrap();
         deck.remove(&cards[1]).unwrap();
-        let mut row = Row::new(cards[0].clone());
-        row.add_right(cards[1].clone());
+        let mut row = Row::new(cards[0]);
+        row.add_right(cards[1]);
         assert_eq!(deck.size, 14);
         assert!(!deck.has_card(&cards[0]));
         assert!(!deck.has_card(&cards[1]));
-        assert_eq!(&row.cards, &vec![cards[0].clone(), cards[1].clone()]);
+        assert_eq!(&row.cards, &vec![cards[0], cards[1]]);
         deck.remove(&cards[2]).unwrap();
-        row.collapse(cards[2].clone(), &mut deck);
+        row.collapse(cards[2], &mut deck).unwrap();
         assert_eq!(deck.size, 15);
-        assert!(deck.cards.get(&cards[0]).unwrap());
-        assert!(deck.cards.get(&cards[1]).unwrap());
+        assert_consistent(&deck);
+        assert!(deck.has_card(&cards[0]));
+        assert!(deck.has_card(&cards[1]));
+    }
+
+    #[test]
+    fn row_resolves_guess_outcomes() {
+        let mut deck = Deck::new(52).unwrap();
+        let cards = [card("a7"), card("b9"), card("c7"), card("d2")];
+        for c in cards.iter() {
+            deck.remove(c).unwrap();
+        }
+        let mut row = Row::new(cards[0]);
+        let resolved = row.resolve(Side::Right, Outcome::Win, cards[1], &mut deck);
+        assert_eq!(resolved, Ok(Command::AddRight));
+        let resolved = row.resolve(Side::Left, Outcome::Push, cards[2], &mut deck);
+        assert_eq!(resolved, Ok(Command::Discard));
+        assert_eq!(row.cards, vec![cards[0], cards[1]]);
+        let resolved = row.resolve(Side::Left, Outcome::Lose, cards[3], &mut deck);
+        assert_eq!(resolved, Ok(Command::Collapse));
+        assert_eq!(row.cards, vec![cards[3]]);
+        assert_eq!(deck.size, 50);
     }
 
     #[test]
     fn row_formats_cards_correctly() {
-        let cards = [
-            String::from("a4"),
-            String::from("b10"),
-            String::from("c12"),
-            String::from("d14"),
-        ];
-        assert_eq!(Row::format_card(&cards[0]), String::from("[♣  4]"));
-        assert_eq!(Row::format_card(&cards[1]), String::from("[♠ 10]"));
+        let cards = [card("a4"), card("b10"), card("c12"), card("d14")];
+        assert_eq!(
+            Row::format_card(&cards[0], Style::default()),
+            String::from("[♣  4]")
+        );
+        assert_eq!(
+            Row::format_card(&cards[1], Style::default()),
+            String::from("[♠ 10]")
+        );
         assert_eq!(
-            Row::format_card(&cards[2]),
+            Row::format_card(&cards[2], Style::default()),
             format!("{}[♥  Q]{}", color::Fg(color::Red), color::Fg(color::Reset))
         );
         assert_eq!(
-            Row::format_card(&cards[3]),
+            Row::format_card(&cards[3], Style::default()),
             format!("{}[♦  A]{}", color::Fg(color::Red), color::Fg(color::Reset))
         );
     }
 
+    #[test]
+    fn row_formats_cards_with_theme() {
+        let style = Style {
+            theme: Theme::preset("four-color").unwrap(),
+            ..Style::default()
+        };
+        assert_eq!(
+            Row::format_card(&card("a4"), style),
+            format!(
+                "{}[♣  4]{}",
+                color::Fg(color::Green),
+                color::Fg(color::Reset)
+            )
+        );
+        assert_eq!(Row::format_card(&card("b4"), style), "[♠  4]");
+        assert_eq!(
+            Row::format_card(&card("d4"), style),
+            format!(
+                "{}[♦  4]{}",
+                color::Fg(color::Blue),
+                color::Fg(color::Reset)
+            )
+        );
+    }
+
     #[test]
     fn row_formats_correctly() {
-        let cards = [String::from("a4"), String::from("b3"), String::from("c12")];
-        let mut row = Row::new(cards[1].clone());
-        row.add_left(cards[0].clone());
-        row.add_right(cards[2].clone());
+        let cards = [card("a4"), card("b3"), card("c12")];
+        let mut row = Row::new(cards[1]);
+        row.add_left(cards[0]);
+        row.add_right(cards[2]);
         assert_eq!(
             format!("{}", row),
             format!(
                 "{} {} {}",
-                Row::format_card(row.cards.get(0).unwrap()),
-                Row::format_card(row.cards.get(1).unwrap()),
-                Row::format_card(row.cards.get(2).unwrap())
+                Row::format_card(&row.cards[0], Style::default()),
+                Row::format_card(&row.cards[1], Style::default()),
+                Row::format_card(&row.cards[2], Style::default())
             )
         );
     }
@@ -1,14 +1,146 @@
-use std::io::{stdin, stdout, Write};
+use std::env;
+use std::fs;
+use std::io::{read_to_string, stdin, stdout, Stdout, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
 
 extern crate termion;
-use termion::{clear, cursor};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::{clear, cursor, terminal_size};
 
-use hilo::{Command, Deck, Table};
+use hilo::practice::Practice;
+use hilo::render::{self, Renderer};
+use hilo::rules::{self, Classic, Rules};
+use hilo::script;
+use hilo::sim::{self, Sim};
+use hilo::{
+    Card, Command, Deck, DeckSpec, Error, Game, Guess, Outcome, Picker, Row, Side, Style, Table,
+    Theme, TieRule,
+};
 
-fn init() -> (Deck, Table) {
-    // TODO print usage
+/// Card counter for hi-lo rows.
+///
+/// Settings missing from the command line are prompted for.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(subcommand)]
+    mode: Option<Mode>,
+    /// Resume a saved session
+    #[arg(long, global = true, value_name = "FILE", conflicts_with_all = ["deck_size", "rows", "cards", "tie_rule", "rules"])]
+    load: Option<PathBuf>,
+    /// Number of cards in the deck, a multiple of 4 no larger than 52
+    #[arg(long, global = true, value_name = "N")]
+    deck_size: Option<usize>,
+    /// Number of rows on the table
+    #[arg(long, global = true, value_name = "N")]
+    rows: Option<usize>,
+    /// Initial card of every row, e.g. AC,3s,7h or a14,b3,c7
+    #[arg(long, global = true, value_name = "CARDS", value_delimiter = ',')]
+    cards: Option<Vec<Card>>,
+    /// What a tie does: lose, push or higher-or-equal
+    #[arg(long, global = true, value_name = "RULE")]
+    tie_rule: Option<TieRule>,
+    /// Game variant: classic, or target-N for rows complete at N cards
+    #[arg(long, global = true, value_name = "RULES")]
+    rules: Option<String>,
+    /// Print without colors; also set by the NO_COLOR environment variable
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Print without any escape sequences; the default when not writing to
+    /// a terminal
+    #[arg(long, global = true)]
+    plain: bool,
+    /// Replace suit symbols and arrows with ASCII letters
+    #[arg(long, global = true)]
+    ascii: bool,
+    /// Color theme: default, high-contrast, deuteranopia or four-color
+    #[arg(long, global = true, value_name = "NAME")]
+    theme: Option<String>,
+    /// Config file setting the theme and its colors [default:
+    /// ~/.config/hilo/config.toml]
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Mode {
+    /// Apply a move script and print the resulting table and odds
+    ///
+    /// Every line holds a row number, a command or guess and, unless
+    /// removing, the drawn card: "2 al c5", "1 c d9", "3 dr", "2 hr d9". The
+    /// table is set up with --deck-size and --cards, or --load.
+    Run {
+        /// Move script, or - for stdin
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+    /// Practice alone: the cards are dealt for you to guess higher or lower
+    ///
+    /// The rows are dealt from a deck shuffled with the seed, unless set
+    /// with --cards. The deck, rows and rules are set with --deck-size,
+    /// --rows, --tie-rule and --rules.
+    Practice {
+        /// Seed of the shuffle [default: random]
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Play many random games with every built-in strategy and compare them
+    ///
+    /// A game is won once a row is complete under --rules, target-5 by
+    /// default, and lost after running out of lives. The deck and rows are
+    /// set with --deck-size, --rows and --tie-rule.
+    Sim {
+        /// Number of games per strategy
+        #[arg(long, default_value_t = 10000)]
+        games: usize,
+        /// Seed of the shuffles
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Collapses losing a game
+        #[arg(long, default_value_t = 3)]
+        lives: usize,
+    },
+}
+
+/// Exits with a usage error for an invalid flag value.
+fn invalid(e: Error) -> ! {
+    Args::command().error(ErrorKind::ValueValidation, e).exit()
+}
+
+fn init(args: &Args) -> (Deck, Table) {
+    let mut deck = match args.deck_size {
+        Some(size) => Deck::new(size).unwrap_or_else(|e| invalid(e)),
+        None => prompt_deck(),
+    };
+    if let Some(tie_rule) = args.tie_rule {
+        deck.set_tie_rule(tie_rule);
+    }
+    let rows = match (args.rows, &args.cards) {
+        (Some(rows), _) => rows,
+        (None, Some(cards)) => cards.len(),
+        (None, None) => prompt_rows(),
+    };
+    let table = match &args.cards {
+        Some(cards) => {
+            let table = Table::new(rows, cards.clone()).unwrap_or_else(|e| invalid(e));
+            remove_cards(&mut deck, cards).unwrap_or_else(|e| invalid(e));
+            table
+        }
+        None => prompt_cards(&mut deck, rows),
+    };
+    (deck, table)
+}
+
+fn prompt_deck() -> Deck {
     print!("{}{}", clear::All, cursor::Goto(1, 1,));
-    let mut deck: Deck;
     loop {
         print!("Deck size? ");
         stdout().flush().unwrap();
@@ -22,174 +154,447 @@ fn init() -> (Deck, Table) {
                 continue;
             }
         };
-        deck = match Deck::new(size) {
-            Ok(deck) => deck,
-            Err(e) => {
-                println!("\n{}", e.to_string());
-                continue;
-            }
-        };
-        break;
+        match Deck::new(size) {
+            Ok(deck) => return deck,
+            Err(e) => println!("\n{}", e),
+        }
     }
-    let rows: usize;
+}
+
+fn prompt_rows() -> usize {
     loop {
         print!("\n\nRows? ");
         stdout().flush().unwrap();
         let mut input = String::new();
         stdin().read_line(&mut input).unwrap();
         input = input.trim().to_string();
-        rows = match input.parse::<usize>() {
-            Ok(rows) => rows,
-            Err(_) => {
-                println!("\nInvalid input!");
-                continue;
-            }
-        };
-        break;
+        match input.parse::<usize>() {
+            Ok(0) => println!("\n{}", Error::NoRows),
+            Ok(rows) => return rows,
+            Err(_) => println!("\nInvalid input!"),
+        }
     }
-    let mut cards: Vec<String>;
-    let table: Table;
+}
+
+fn prompt_cards(deck: &mut Deck, rows: usize) -> Table {
     loop {
         print!("Inital cards? ");
         stdout().flush().unwrap();
         let input = read_input();
-        cards = input.split(",").map(|c| String::from(c)).collect();
-        // TODO more verbose user information
-        if cards.len() != rows {
-            println!("\nCard amount must match row count");
-            continue;
-        }
-        if !cards.iter().all(|c| deck.is_card(c) && deck.has_card(c)) {
-            println!("\nInvalid card(s)");
-            continue;
-        }
-        for c in cards.iter() {
-            deck.remove(c).unwrap();
-        }
-        table = match Table::new(rows, cards) {
+        let cards: Vec<Card> = match input.split(',').map(|c| c.parse()).collect() {
+            Ok(cards) => cards,
+            Err(e) => {
+                println!("\n{}", e);
+                continue;
+            }
+        };
+        let table = match Table::new(rows, cards.clone()) {
             Ok(table) => table,
-            Err(_) => {
-                println!("\nInvalid input!");
+            Err(e) => {
+                println!("\n{}", e);
                 continue;
             }
         };
-        break;
+        if let Err(e) = remove_cards(deck, &cards) {
+            println!("\n{}", e);
+            continue;
+        }
+        return table;
     }
-    (deck, table)
 }
 
-// TODO select row using arrow keys
-fn game_loop(mut deck: Deck, mut table: Table) {
-    let mut row_num: usize = 0;
-    let input_row = (table.rows.len() * 2 + 1) as u16;
-    let mut input: String;
-    table.print(&deck, row_num);
+/// Removes all cards from the deck, or none of them if one cannot be removed.
+fn remove_cards(deck: &mut Deck, cards: &[Card]) -> Result<(), Error> {
+    for (i, c) in cards.iter().enumerate() {
+        if let Err(e) = deck.remove(c) {
+            for c in cards[..i].iter() {
+                deck.add(*c).unwrap();
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// File name of new sessions, numbered from the second one on.
+const SESSION_NAME: &str = "hilo-session";
+
+const KEYS_HELP: &str =
+    "[↑↓] row  [c] collapse  [l|r] add  [L|R] remove  [u|U] undo/redo  [q] quit";
+
+const PRACTICE_HELP: &str = "[↑↓] row  [←→] end  [h] higher  [l] lower  [q] quit";
+
+/// How often to check for a resized terminal while waiting for a key.
+const RESIZE_POLL: Duration = Duration::from_millis(200);
+
+/// Switches the terminal to raw mode for reading single keys.
+fn raw_stdout() -> RawTerminal<Stdout> {
+    let mut stdout = match stdout().into_raw_mode() {
+        Ok(stdout) => stdout,
+        Err(e) => {
+            eprintln!("Cannot read keys from this terminal: {}", e);
+            process::exit(1);
+        }
+    };
+    write!(stdout, "{}", cursor::Hide).unwrap();
+    stdout
+}
+
+/// Clears the screen before raw mode ends.
+fn restore(mut stdout: RawTerminal<Stdout>) {
+    write!(
+        stdout,
+        "{}{}{}",
+        clear::All,
+        cursor::Goto(1, 1),
+        cursor::Show
+    )
+    .unwrap();
+    stdout.flush().unwrap();
+}
+
+/// The first of `hilo-session.json`, `hilo-session-2.json`, ... that does
+/// not exist yet, so a new game never overwrites a session that may still
+/// be resumed.
+fn new_session() -> PathBuf {
+    (1..)
+        .map(|n| match n {
+            1 => PathBuf::from(format!("{}.json", SESSION_NAME)),
+            n => PathBuf::from(format!("{}-{}.json", SESSION_NAME, n)),
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+fn game_loop(mut game: Game, session: &Path, style: Style) {
+    let mut stdout = raw_stdout();
+    let keys = read_keys();
+    let mut renderer = Renderer::new(terminal_size().unwrap_or((80, 24)));
+    let (mut sel_row, mut side) = (0, Side::Left);
+    let mut message = format!("Autosaving to {}", session.display());
+    let mut changed = false;
     loop {
-        print!("{}", clear::All);
-        table.print(&deck, row_num);
-        loop {
-            print!(
-                "{}{}{}",
-                cursor::Goto(1, input_row),
-                clear::CurrentLine,
-                "Row? "
-            );
-            stdout().flush().unwrap();
-            input = read_input();
-            row_num = match input.parse::<usize>() {
-                Ok(n) => n,
-                Err(_) => {
-                    print!("\nInvalid input!");
+        if changed {
+            if let Err(e) = game.save(session) {
+                message = format!("Autosave failed: {}", e);
+            }
+            changed = false;
+        }
+        let mut lines = frame(&game, sel_row, side, style, renderer.size());
+        lines.push(String::new());
+        lines.push(message.clone());
+        renderer.draw(&mut stdout, &lines).unwrap();
+        let key = match wait(&keys, &mut renderer) {
+            Ok(Some(key)) => key,
+            Ok(None) => continue,
+            Err(_) => break,
+        };
+        message.clear();
+        if select(key, game.table().rows.len(), &mut sel_row, &mut side) {
+            continue;
+        }
+        let result = match key {
+            Key::Char('u') => game.undo(),
+            Key::Char('U') => game.redo(),
+            Key::Char('L') => game.apply(sel_row, Command::RemoveLeft, None),
+            Key::Char('R') => game.apply(sel_row, Command::RemoveRight, None),
+            Key::Char(c @ ('c' | 'l' | 'r')) => {
+                let command = match c {
+                    'c' => Command::Collapse,
+                    'l' => Command::AddLeft,
+                    _ => Command::AddRight,
+                };
+                let mut prompt = Prompt {
+                    keys: &keys,
+                    renderer: &mut renderer,
+                    out: &mut stdout,
+                };
+                let apply = |game: &mut Game, card| game.apply(sel_row, command, Some(card));
+                if !read_card(&mut prompt, &mut game, sel_row, side, style, apply) {
                     continue;
                 }
-            };
-            row_num = row_num - 1;
-            if !table.has_row(row_num) {
-                print!("\nRow does not exist!");
-                continue;
+                Ok(())
             }
-            // TODO unit tests for table.print
-            // TODO does the whole table need to be reprinted?
-            table.print(&deck, row_num);
-            print!("{}{}", cursor::Goto(1, input_row + 1), clear::CurrentLine,);
-            break;
-        }
-        let row = table.rows.get_mut(row_num).unwrap();
-        let command: Command;
-        loop {
-            print!(
-                "{}{}{}",
-                cursor::Goto(1, input_row + 2),
-                clear::CurrentLine,
-                "Command? [c|al|ar|dl|dr]? "
-            );
-            stdout().flush().unwrap();
-            input = read_input();
-            command = match input.as_str() {
-                "c" => Command::Collapse,
-                "al" => Command::AddLeft,
-                "ar" => Command::AddRight,
-                "dl" => {
-                    if row.len() < 2 {
-                        print!("\nCannot remove last card in row!");
-                        continue;
-                    }
-                    Command::RemoveLeft
-                }
-                "dr" => {
-                    if row.len() < 2 {
-                        print!("\nCannot remove last card in row!");
-                        continue;
-                    }
-                    Command::RemoveRight
-                }
-                _ => {
-                    print!("\nInvalid command!");
+            Key::Char(c @ ('+' | '-')) => {
+                let guess = match c {
+                    '+' => Guess::Higher,
+                    _ => Guess::Lower,
+                };
+                let mut prompt = Prompt {
+                    keys: &keys,
+                    renderer: &mut renderer,
+                    out: &mut stdout,
+                };
+                let mut outcome = None;
+                let apply = |game: &mut Game, card| {
+                    outcome = Some(game.guess(sel_row, side, guess, card)?);
+                    Ok(())
+                };
+                if !read_card(&mut prompt, &mut game, sel_row, side, style, apply) {
                     continue;
                 }
-            };
-            print!("{}{}", cursor::Goto(1, input_row + 3), clear::CurrentLine,);
-            break;
-        }
-        match command {
-            Command::RemoveLeft => {
-                row.remove_left(&mut deck);
-                continue;
+                message = match outcome {
+                    Some(Outcome::Win) => String::from("Won: the card extends the row"),
+                    Some(Outcome::Push) => String::from("Tie: the card is discarded"),
+                    _ => String::from("Lost: the row collapses onto the card"),
+                };
+                Ok(())
             }
-            Command::RemoveRight => {
-                row.remove_right(&mut deck);
-                continue;
+            Key::Char('q') | Key::Ctrl('c') => break,
+            _ => continue,
+        };
+        match result {
+            Ok(()) => changed = true,
+            Err(e) => message = failure(&e, style),
+        }
+    }
+    restore(stdout);
+    if session.exists() {
+        println!("Resume with: hilo --load {}", session.display());
+    }
+}
+
+fn practice_loop(mut practice: Practice, seed: u64, style: Style) {
+    let mut stdout = raw_stdout();
+    let keys = read_keys();
+    let mut renderer = Renderer::new(terminal_size().unwrap_or((80, 24)));
+    let (mut sel_row, mut side) = (0, Side::Left);
+    let mut message = vec![String::from("Higher or lower?")];
+    loop {
+        let mut lines = practice.lines(sel_row, side, style);
+        lines.push(String::new());
+        lines.extend(message.iter().cloned());
+        let score = practice.score();
+        lines.push(format!(
+            "Won {} of {}, {} with the odds; {} cards left, seed {}",
+            score.wins,
+            score.rounds,
+            score.matched,
+            practice.deck().size(),
+            seed
+        ));
+        lines.extend(practice.rules().score_line(practice.table()));
+        lines.push(help(PRACTICE_HELP, style));
+        renderer.draw(&mut stdout, &lines).unwrap();
+        let key = match wait(&keys, &mut renderer) {
+            Ok(Some(key)) => key,
+            Ok(None) => continue,
+            Err(_) => break,
+        };
+        if select(key, practice.table().rows.len(), &mut sel_row, &mut side) {
+            continue;
+        }
+        let guess = match key {
+            Key::Char('h') => Guess::Higher,
+            Key::Char('l') => Guess::Lower,
+            Key::Char('q') | Key::Ctrl('c') => break,
+            _ => continue,
+        };
+        message = match practice.guess(sel_row, side, guess) {
+            Ok(round) => round.describe(style),
+            Err(e) => vec![failure(&e, style)],
+        };
+    }
+    restore(stdout);
+}
+
+/// Moves the selected row or end of a table of `rows` rows for the arrow,
+/// j/k and digit keys. Returns false for any other key.
+fn select(key: Key, rows: usize, sel_row: &mut usize, side: &mut Side) -> bool {
+    match key {
+        Key::Up | Key::Char('k') => *sel_row = sel_row.saturating_sub(1),
+        Key::Down | Key::Char('j') => *sel_row = (*sel_row + 1).min(rows.saturating_sub(1)),
+        Key::Char(c @ '1'..='9') => {
+            let row = c as usize - '1' as usize;
+            if row < rows {
+                *sel_row = row;
             }
-            _ => (),
         }
-        let mut card: String;
-        loop {
-            print!(
-                "{}{}{}",
-                cursor::Goto(1, input_row + 4),
-                clear::CurrentLine,
-                "Card? "
-            );
-            stdout().flush().unwrap();
-            card = read_input();
-            if !deck.is_card(&card) {
-                print!("\nInvalid card!");
-                continue;
+        Key::Left => *side = Side::Left,
+        Key::Right => *side = Side::Right,
+        _ => return false,
+    }
+    true
+}
+
+/// Reads keys on a separate thread so the screen can be redrawn while
+/// waiting for input.
+fn read_keys() -> Receiver<Key> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for key in stdin().keys() {
+            match key {
+                Ok(key) if sender.send(key).is_ok() => (),
+                _ => break,
             }
-            if !deck.has_card(&card) {
-                print!("\n Card not in deck!");
-                continue;
+        }
+    });
+    receiver
+}
+
+/// Waits for the next key, returning `None` if the terminal was resized in
+/// the meantime and the frame needs to be laid out again.
+fn wait(keys: &Receiver<Key>, renderer: &mut Renderer) -> Result<Option<Key>, RecvTimeoutError> {
+    loop {
+        match keys.recv_timeout(RESIZE_POLL) {
+            Ok(key) => return Ok(Some(key)),
+            Err(RecvTimeoutError::Timeout) => {
+                let size = terminal_size().unwrap_or(renderer.size());
+                if size != renderer.size() {
+                    renderer.resize(size);
+                    return Ok(None);
+                }
             }
-            print!("{}{}", cursor::Goto(1, input_row + 5), clear::CurrentLine,);
-            break;
+            Err(e) => return Err(e),
         }
-        match command {
-            Command::Collapse => row.collapse(card, &mut deck),
-            Command::AddLeft => row.add_left(card),
-            Command::AddRight => row.add_right(card),
-            _ => (),
+    }
+}
+
+/// The table, best move and key help, laid out for a terminal of `size`.
+fn frame(game: &Game, sel_row: usize, side: Side, style: Style, size: (u16, u16)) -> Vec<String> {
+    let recommendations = game.recommend();
+    let best = recommendations.first().copied();
+    let mut lines = game
+        .table()
+        .lines(game.deck(), best, sel_row, style, size.0 as usize);
+    lines.extend(game.status_lines());
+    let guess_help = format!(
+        "Guess on row {} {} [←→]: [+] higher  [-] lower",
+        sel_row + 1,
+        side
+    );
+    lines.push(help(&guess_help, style));
+    lines.push(help(KEYS_HELP, style));
+    lines
+}
+
+/// An error as shown on screen, giving only ASCII examples in ASCII mode.
+fn failure(e: &Error, style: Style) -> String {
+    match style.ascii {
+        true => format!("{}!", e.ascii()),
+        false => format!("{}!", e),
+    }
+}
+
+/// Key help with the arrows spelled out in ASCII mode.
+fn help(keys: &str, style: Style) -> String {
+    match style.ascii {
+        true => keys
+            .replace("[↑↓]", "[up|down]")
+            .replace("[←→]", "[left|right]"),
+        false => keys.to_string(),
+    }
+}
+
+/// Terminal state needed to draw the card prompt.
+struct Prompt<'a, W: Write> {
+    keys: &'a Receiver<Key>,
+    renderer: &'a mut Renderer,
+    out: &'a mut W,
+}
+
+/// Prompts for the drawn card below the table and applies it to the game.
+///
+/// The card is picked from a grid of the deck below the prompt, either with
+/// the arrow keys or by typing it. Returns false if cancelled with escape.
+fn read_card<W: Write>(
+    prompt: &mut Prompt<W>,
+    game: &mut Game,
+    row: usize,
+    side: Side,
+    style: Style,
+    mut apply: impl FnMut(&mut Game, Card) -> Result<(), Error>,
+) -> bool {
+    let mut picker = Picker::new(game.deck().spec());
+    let mut input = String::new();
+    let mut typed: Result<Option<Card>, Error> = Ok(None);
+    let mut error: Option<Error> = None;
+    loop {
+        let feedback = match (&error, &typed) {
+            (Some(e), _) | (None, Err(e)) => failure(e, style),
+            (None, Ok(_)) => match check_card(game, picker.selected()) {
+                Ok(card) => {
+                    let check = if style.ascii { "ok" } else { "\u{2713}" };
+                    format!("{} {}", check, Row::format_card(&card, style))
+                }
+                Err(e) => e.to_string(),
+            },
         };
+        let mut lines = frame(game, row, side, style, prompt.renderer.size());
+        let line = lines.len() as u16 + 1;
+        let label = "Card? [arrows|qh|c12, enter to pick, esc to cancel] ";
+        lines.push(format!("{}{}", label, input));
+        lines.push(feedback);
+        lines.push(String::new());
+        lines.extend(picker.lines(game.deck(), style));
+        prompt.renderer.draw(prompt.out, &lines).unwrap();
+        write!(
+            prompt.out,
+            "{}{}",
+            cursor::Goto(
+                (render::width(label) + render::width(&input)) as u16 + 1,
+                line
+            ),
+            cursor::Show
+        )
+        .unwrap();
+        prompt.out.flush().unwrap();
+        let key = wait(prompt.keys, prompt.renderer);
+        write!(prompt.out, "{}", cursor::Hide).unwrap();
+        let key = match key {
+            Ok(Some(key)) => key,
+            Ok(None) => continue,
+            Err(_) => return false,
+        };
+        error = None;
+        let (rows, cols) = match key {
+            Key::Up => (-1, 0),
+            Key::Down => (1, 0),
+            Key::Left => (0, -1),
+            Key::Right => (0, 1),
+            _ => (0, 0),
+        };
+        if (rows, cols) != (0, 0) {
+            picker.move_by(rows, cols);
+            input.clear();
+            typed = Ok(None);
+            continue;
+        }
+        match key {
+            Key::Char('\n') => match check_card(game, picker.selected()) {
+                Ok(card) => match apply(game, card) {
+                    Ok(()) => return true,
+                    Err(e) => error = Some(e),
+                },
+                Err(e) => error = Some(e),
+            },
+            Key::Backspace => {
+                input.pop();
+                typed = picker.type_input(&input);
+            }
+            Key::Esc | Key::Ctrl('c') => return false,
+            Key::Char(c) if c != '\t' && (c != ' ' || !input.is_empty()) => {
+                // A complete card starts the next input afresh.
+                if let Ok(Some(_)) = typed {
+                    input.clear();
+                }
+                input.push(c.to_ascii_lowercase());
+                typed = picker.type_input(&input);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Checks the card can still be drawn from the deck.
+fn check_card(game: &Game, card: Card) -> Result<Card, Error> {
+    if !game.deck().is_card(&card) {
+        return Err(Error::CardNotInDeck(card));
+    }
+    if !game.deck().has_card(&card) {
+        return Err(Error::CardAlreadyRemoved(card));
     }
+    Ok(card)
 }
 
 fn read_input() -> String {
@@ -198,7 +603,182 @@ fn read_input() -> String {
     input.trim().to_string().to_lowercase()
 }
 
+/// Sets up a game from flags alone, for modes that cannot prompt.
+fn setup(args: &Args) -> Game {
+    if let Some(path) = &args.load {
+        return Game::load(path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+    }
+    if args.deck_size.is_none() || args.cards.is_none() {
+        Args::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--deck-size and --cards, or --load, are required",
+            )
+            .exit();
+    }
+    start(args)
+}
+
+/// Exits with a usage error for a flag the mode has no use for.
+fn unsupported(flag: &str, mode: &str) -> ! {
+    Args::command()
+        .error(
+            ErrorKind::ArgumentConflict,
+            format!("{} cannot be used with {}", flag, mode),
+        )
+        .exit()
+}
+
+/// The rules set by --rules, if any.
+fn chosen_rules(args: &Args) -> Option<Box<dyn Rules>> {
+    let name = args.rules.as_ref()?;
+    Some(rules::parse(name).unwrap_or_else(|e| invalid(e)))
+}
+
+/// Starts a game from flags and prompts under the rules set by --rules.
+fn start(args: &Args) -> Game {
+    let rules = chosen_rules(args).unwrap_or_else(|| Box::new(Classic));
+    let (deck, table) = init(args);
+    let mut game = Game::new(deck, table);
+    game.set_rules(rules);
+    game
+}
+
+fn run(args: &Args, file: &Path) {
+    let input = if file == Path::new("-") {
+        read_to_string(stdin())
+    } else {
+        fs::read_to_string(file)
+    };
+    let script = input.unwrap_or_else(|e| {
+        eprintln!("{}: {}", file.display(), e);
+        process::exit(1);
+    });
+    let mut game = setup(args);
+    if let Err(e) = script::run(&mut game, &script) {
+        match style(args).ascii {
+            true => eprintln!("{}", e.ascii()),
+            false => eprintln!("{}", e),
+        }
+        process::exit(1);
+    }
+    println!("{}", script::summary(&game, style(args)));
+}
+
+/// Sets up practice from flags, dealing the rows unless given by --cards.
+fn practice(args: &Args, seed: u64) -> Practice {
+    if args.load.is_some() {
+        unsupported("--load", "practice");
+    }
+    let rules = chosen_rules(args);
+    let mut deck = Deck::new(args.deck_size.unwrap_or(52)).unwrap_or_else(|e| invalid(e));
+    if let Some(tie_rule) = args.tie_rule {
+        deck.set_tie_rule(tie_rule);
+    }
+    let mut practice = match &args.cards {
+        Some(cards) => {
+            remove_cards(&mut deck, cards).unwrap_or_else(|e| invalid(e));
+            let table = Table::new(args.rows.unwrap_or(cards.len()), cards.clone())
+                .unwrap_or_else(|e| invalid(e));
+            Practice::new(deck, table, seed)
+        }
+        None => Practice::deal(deck, args.rows.unwrap_or(4), seed).unwrap_or_else(|e| invalid(e)),
+    };
+    if let Some(rules) = rules {
+        practice.set_rules(rules);
+    }
+    practice
+}
+
+/// Compares the built-in strategies on a deck and table set up by flags.
+fn simulate(args: &Args, games: usize, seed: u64, lives: usize) {
+    if args.load.is_some() {
+        unsupported("--load", "sim");
+    }
+    if args.cards.is_some() {
+        unsupported("--cards", "sim");
+    }
+    let spec = DeckSpec::from_size(args.deck_size.unwrap_or(52)).unwrap_or_else(|e| invalid(e));
+    let mut sim = Sim::new(spec)
+        .tie_rule(args.tie_rule.unwrap_or_default())
+        .rows(args.rows.unwrap_or(4))
+        .lives(lives)
+        .seed(seed);
+    if let Some(rules) = chosen_rules(args) {
+        sim = sim.rules(rules);
+    }
+    let mut results = Vec::new();
+    for mut strategy in sim::strategies() {
+        match sim.run(strategy.as_mut(), games) {
+            Ok(stats) => results.push((strategy.name(), stats)),
+            Err(e) => invalid(e),
+        }
+    }
+    print!("{}", sim::report(&results));
+}
+
+/// Output style from the flags, the `NO_COLOR` convention, whether stdout
+/// is a terminal and the config file.
+fn style(args: &Args) -> Style {
+    let escapes = !args.plain && termion::is_tty(&stdout());
+    let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    Style {
+        color: escapes && !args.no_color && !no_color,
+        escapes,
+        ascii: args.ascii,
+        theme: theme(args),
+    }
+}
+
+/// Loads the theme from `--config`, or from the default config file if there
+/// is one.
+fn theme(args: &Args) -> Theme {
+    let path = args.config.clone().or_else(|| {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join("hilo").join("config.toml")).filter(|path| path.exists())
+    });
+    let config = match &path {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path.display(), e);
+            process::exit(1);
+        }),
+        None => String::new(),
+    };
+    Theme::load(&config, args.theme.as_deref()).unwrap_or_else(|e| match (&e, path) {
+        (Error::InvalidConfig(_), Some(path)) => {
+            eprintln!("{}: {}", path.display(), e);
+            process::exit(1);
+        }
+        _ => invalid(e),
+    })
+}
+
 fn main() {
-    let (deck, table) = init();
-    game_loop(deck, table);
+    let args = Args::parse();
+    match &args.mode {
+        Some(Mode::Run { file }) => return run(&args, file),
+        Some(Mode::Practice { seed }) => {
+            let seed = seed.unwrap_or_else(rand::random);
+            return practice_loop(practice(&args, seed), seed, style(&args));
+        }
+        Some(Mode::Sim { games, seed, lives }) => return simulate(&args, *games, *seed, *lives),
+        None => {}
+    }
+    let style = style(&args);
+    let (game, session) = match &args.load {
+        Some(path) => match Game::load(path) {
+            Ok(game) => (game, path.clone()),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        },
+        None => (start(&args), new_session()),
+    };
+    game_loop(game, &session, style);
 }
@@ -0,0 +1,246 @@
+use termion::{color, style};
+
+use crate::theme::Color;
+use crate::{Card, Deck, DeckSpec, Error, Rank, Style, Suit};
+
+/// Cursor over a grid of every card in a deck's composition, one row per
+/// suit and one column per rank, with jokers on a row of their own.
+pub struct Picker {
+    suits: Vec<Suit>,
+    ranks: Vec<Rank>,
+    row: usize,
+    col: usize,
+}
+
+impl Picker {
+    pub fn new(spec: &DeckSpec) -> Picker {
+        let mut suits = spec.suits.clone();
+        if spec.jokers > 0 {
+            suits.push(Suit::Joker);
+        }
+        let ranks = (spec.lowest.value()..=spec.highest.value())
+            .filter_map(Rank::new)
+            .collect();
+        Picker {
+            suits,
+            ranks,
+            row: 0,
+            col: 0,
+        }
+    }
+
+    /// The card under the cursor.
+    pub fn selected(&self) -> Card {
+        match self.suits[self.row] {
+            Suit::Joker => Card::joker(),
+            suit => Card::new(suit, self.ranks[self.col]),
+        }
+    }
+
+    /// Moves the cursor, stopping at the edges of the grid.
+    pub fn move_by(&mut self, rows: isize, cols: isize) {
+        self.row = self
+            .row
+            .saturating_add_signed(rows)
+            .min(self.suits.len() - 1);
+        self.col = self
+            .col
+            .saturating_add_signed(cols)
+            .min(self.ranks.len() - 1);
+        if self.suits[self.row] == Suit::Joker {
+            self.col = 0;
+        }
+    }
+
+    /// Moves the cursor to the card, if it is part of the grid.
+    pub fn select(&mut self, card: &Card) -> bool {
+        let row = self.suits.iter().position(|s| *s == card.suit);
+        let col = match card.suit {
+            Suit::Joker => Some(0),
+            _ => self.ranks.iter().position(|r| *r == card.rank),
+        };
+        match (row, col) {
+            (Some(row), Some(col)) => {
+                self.row = row;
+                self.col = col;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves the cursor according to typed input, a card in any notation
+    /// accepted by [`Card`]'s parser ("qh", "10d", "c12").
+    ///
+    /// A rank on its own moves to that rank within the current suit. Returns
+    /// the card once the input names one, or an error if the input cannot
+    /// become a card.
+    pub fn type_input(&mut self, input: &str) -> Result<Option<Card>, Error> {
+        if let Ok(card) = input.parse::<Card>() {
+            return self.select_typed(card, input).map(Some);
+        }
+        let input = input.trim().to_lowercase();
+        if let Some(rank) = Rank::from_label(&input) {
+            if self.suits[self.row] != Suit::Joker {
+                self.select_typed(Card::new(self.suits[self.row], rank), &input)?;
+            }
+            return Ok(None);
+        }
+        let internal = input.len() <= 2
+            && input.starts_with(['a', 'b', 'c', 'd'])
+            && input[1..].chars().all(|c| c.is_ascii_digit());
+        let symbol = Suit::from_name(&input).is_some();
+        let words = input.contains(' ') || input.chars().all(|c| c.is_ascii_alphabetic());
+        match input == "1" || internal || symbol || words {
+            true => Ok(None),
+            false => Err(Error::UnknownCard(input)),
+        }
+    }
+
+    fn select_typed(&mut self, card: Card, input: &str) -> Result<Card, Error> {
+        match self.select(&card) {
+            true => Ok(card),
+            false => Err(Error::UnknownCard(input.to_string())),
+        }
+    }
+
+    /// The grid, greying out cards already drawn from the deck and
+    /// highlighting the cursor.
+    pub fn lines(&self, deck: &Deck, style: Style) -> Vec<String> {
+        self.suits
+            .iter()
+            .enumerate()
+            .map(|(row, suit)| {
+                let cards: Vec<Card> = match suit {
+                    Suit::Joker => vec![Card::joker()],
+                    _ => self.ranks.iter().map(|r| Card::new(*suit, *r)).collect(),
+                };
+                let cells: Vec<String> = cards
+                    .iter()
+                    .enumerate()
+                    .map(|(col, card)| {
+                        let selected = row == self.row && col == self.col;
+                        Picker::format_cell(card, deck.has_card(card), selected, style)
+                    })
+                    .collect();
+                cells.join(" ")
+            })
+            .collect()
+    }
+
+    fn format_cell(card: &Card, remaining: bool, selected: bool, style: Style) -> String {
+        let mut cell = format!("{:>2}{}", card.rank.label(), style.suit(card.suit));
+        if !style.escapes {
+            if !remaining {
+                cell = String::from("  .");
+            }
+            if selected {
+                cell = format!(">{}", cell.trim_start());
+            }
+            return cell;
+        }
+        let suit_color = style.theme.suit(card.suit);
+        if !remaining && style.colored() {
+            cell = format!(
+                "{}{}{}",
+                style.theme.drawn.fg(),
+                cell,
+                color::Fg(color::Reset)
+            );
+        } else if !remaining {
+            cell = format!("{}{}{}", style::Faint, cell, style::NoFaint);
+        } else if suit_color != Color::Default && style.colored() {
+            cell = format!("{}{}{}", suit_color.fg(), cell, color::Fg(color::Reset));
+        }
+        if selected {
+            cell = format!("{}{}{}", style::Invert, cell, style::NoInvert);
+        }
+        cell
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::card;
+
+    #[test]
+    fn picker_moves_within_grid() {
+        let mut picker = Picker::new(&DeckSpec::from_size(16).unwrap());
+        assert_eq!(picker.selected(), card("a11"));
+        picker.move_by(-1, -1);
+        assert_eq!(picker.selected(), card("a11"));
+        picker.move_by(2, 1);
+        assert_eq!(picker.selected(), card("c12"));
+        picker.move_by(9, 9);
+        assert_eq!(picker.selected(), card("d14"));
+    }
+
+    #[test]
+    fn picker_has_joker_row() {
+        let mut picker = Picker::new(&DeckSpec::from_size(16).unwrap().jokers(2));
+        picker.move_by(0, 3);
+        picker.move_by(4, 0);
+        assert_eq!(picker.selected(), Card::joker());
+        picker.move_by(0, 2);
+        assert_eq!(picker.selected(), Card::joker());
+        picker.move_by(-1, 0);
+        assert_eq!(picker.selected(), card("d11"));
+    }
+
+    #[test]
+    fn picker_follows_typed_input() {
+        let mut picker = Picker::new(&DeckSpec::new());
+        assert_eq!(picker.type_input("QH"), Ok(Some(card("c12"))));
+        assert_eq!(picker.selected(), card("c12"));
+        assert_eq!(picker.type_input("10d"), Ok(Some(card("d10"))));
+        assert_eq!(picker.type_input("ts"), Ok(Some(card("b10"))));
+        assert_eq!(picker.type_input("a2"), Ok(Some(card("a2"))));
+        assert_eq!(picker.type_input("k"), Ok(None));
+        assert_eq!(picker.selected(), card("a13"));
+        for partial in ["1", "c1", "j", "♥", "queen of"] {
+            assert_eq!(picker.type_input(partial), Ok(None));
+        }
+        assert_eq!(picker.type_input("queen of spades"), Ok(Some(card("b12"))));
+        assert_eq!(
+            picker.type_input("q9"),
+            Err(Error::UnknownCard(String::from("q9")))
+        );
+        assert_eq!(
+            picker.type_input("jk"),
+            Err(Error::UnknownCard(String::from("jk")))
+        );
+    }
+
+    #[test]
+    fn picker_greys_out_drawn_cards() {
+        let mut deck = Deck::new(8).unwrap();
+        deck.remove(&card("b13")).unwrap();
+        let mut picker = Picker::new(deck.spec());
+        picker.move_by(0, 1);
+        let lines = picker.lines(
+            &deck,
+            Style {
+                color: false,
+                ..Style::default()
+            },
+        );
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            format!(" K♣ {} A♣{}", style::Invert, style::NoInvert)
+        );
+        assert_eq!(
+            lines[1],
+            format!("{} K♠{}  A♠", style::Faint, style::NoFaint)
+        );
+        assert_eq!(lines[2], " K♥  A♥");
+        let style = Style {
+            ascii: true,
+            ..Style::plain()
+        };
+        let lines = picker.lines(&deck, style);
+        assert_eq!(lines[0], " KC >AC");
+        assert_eq!(lines[1], "  .  AS");
+    }
+}
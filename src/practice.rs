@@ -0,0 +1,330 @@
+//! Solo practice against a shuffled deck.
+//!
+//! The program deals the cards: the player bets higher or lower on one end
+//! of a row, the top card of a [`Shoe`] is revealed and the row is extended
+//! or collapsed accordingly. Every bet is compared with the odds.
+
+use crate::rules::{Classic, Rules};
+use crate::{Card, Deck, Error, Guess, Outcome, Ratio, Row, Shoe, Side, Style, Table};
+
+/// A revealed card and how the bet on it went.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Round {
+    pub row: usize,
+    pub side: Side,
+    pub guess: Guess,
+    pub card: Card,
+    pub outcome: Outcome,
+    /// Chance the guess had of winning.
+    pub chance: Ratio,
+    /// The guess with the better odds and its chance of winning.
+    pub best: Guess,
+    pub best_chance: Ratio,
+}
+
+impl Round {
+    /// Whether the guess was at least as likely to win as the other one.
+    pub fn matched_odds(&self) -> bool {
+        self.chance >= self.best_chance
+    }
+
+    /// What was drawn and how the guess compared with the odds.
+    pub fn describe(&self, style: Style) -> Vec<String> {
+        let result = match self.outcome {
+            Outcome::Win => String::from("wins"),
+            Outcome::Push => String::from("ties and pushes"),
+            Outcome::Lose => format!("loses, row {} collapses", self.row + 1),
+        };
+        let odds = match self.matched_odds() {
+            true => format!(
+                "Good call: {} had the better odds ({:.2})",
+                self.guess,
+                self.chance.to_f32()
+            ),
+            false => format!(
+                "The odds favored {} ({:.2} against {:.2})",
+                self.best,
+                self.best_chance.to_f32(),
+                self.chance.to_f32()
+            ),
+        };
+        vec![
+            format!(
+                "Drew {}: {} {}",
+                Row::format_card(&self.card, style),
+                self.guess,
+                result
+            ),
+            odds,
+        ]
+    }
+}
+
+/// Bets placed so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub rounds: usize,
+    pub wins: usize,
+    /// Bets on the guess with the better odds.
+    pub matched: usize,
+}
+
+pub struct Practice {
+    deck: Deck,
+    table: Table,
+    shoe: Shoe,
+    rules: Box<dyn Rules>,
+    score: Score,
+}
+
+impl Practice {
+    /// Practice under the [`Classic`] rules on the table, whose cards must
+    /// already be removed from the deck, drawing from the rest of the deck
+    /// shuffled with `seed`.
+    pub fn new(deck: Deck, table: Table, seed: u64) -> Practice {
+        let shoe = Shoe::new(&deck, seed);
+        Practice {
+            deck,
+            table,
+            shoe,
+            rules: Box::new(Classic),
+            score: Score::default(),
+        }
+    }
+
+    /// Practice on `rows` rows dealt from the deck shuffled with `seed`.
+    pub fn deal(mut deck: Deck, rows: usize, seed: u64) -> Result<Practice, Error> {
+        let mut shoe = Shoe::new(&deck, seed);
+        let cards = (0..rows)
+            .map(|_| shoe.draw(&mut deck))
+            .collect::<Result<Vec<Card>, Error>>()?;
+        Ok(Practice {
+            table: Table::new(rows, cards)?,
+            deck,
+            shoe,
+            rules: Box::new(Classic),
+            score: Score::default(),
+        })
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub fn rules(&self) -> &dyn Rules {
+        self.rules.as_ref()
+    }
+
+    pub fn set_rules(&mut self, rules: Box<dyn Rules>) {
+        self.rules = rules;
+    }
+
+    /// Bets on the end of the row and reveals the next card, which changes
+    /// the row under the rules as
+    /// [`Command::from_outcome`](crate::Command::from_outcome).
+    pub fn guess(&mut self, row: usize, side: Side, guess: Guess) -> Result<Round, Error> {
+        let base = match self.table.rows.get(row) {
+            Some(r) if !self.rules.allows(r, side) => {
+                return Err(Error::GuessNotAllowed { row, side })
+            }
+            Some(r) => *r.get(side),
+            None => return Err(Error::RowNotFound(row)),
+        };
+        let best = self.deck.recommend(&base);
+        let chance = self.deck.chance(&base, guess);
+        let best_chance = self.deck.chance(&base, best);
+        let card = self.shoe.draw(&mut self.deck)?;
+        let outcome = self
+            .rules
+            .outcome(self.deck.tie_rule(), guess, &base, &card);
+        let cards = &mut self.table.rows[row];
+        self.shoe
+            .resolve(cards, side, outcome, card, &mut self.deck)?;
+        let round = Round {
+            row,
+            side,
+            guess,
+            card,
+            outcome,
+            chance,
+            best,
+            best_chance,
+        };
+        self.score.rounds += 1;
+        self.score.wins += (outcome == Outcome::Win) as usize;
+        self.score.matched += round.matched_odds() as usize;
+        Ok(round)
+    }
+
+    /// The rows without odds, marking the selected end of the selected row.
+    pub fn lines(&self, sel_row: usize, side: Side, style: Style) -> Vec<String> {
+        let (left, right) = if style.ascii {
+            ("<", ">")
+        } else {
+            ("◀", "▶")
+        };
+        self.table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let (left, right) = match (i == sel_row, side) {
+                    (true, Side::Left) => (left, " "),
+                    (true, Side::Right) => (" ", right),
+                    (false, _) => (" ", " "),
+                };
+                format!("{}: {} {} {}", i + 1, left, row.format(style), right)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::{card, deal};
+    use crate::rules::TargetLength;
+    use crate::TieRule;
+
+    fn practice() -> Practice {
+        let mut deck = Deck::new(52).unwrap();
+        deck.set_tie_rule(TieRule::Push);
+        Practice::deal(deck, 3, 11).unwrap()
+    }
+
+    #[test]
+    fn practice_deals_rows() {
+        let practice = practice();
+        assert_eq!(practice.table().rows.len(), 3);
+        assert_eq!(practice.deck().size(), 49);
+        let cards: Vec<Card> = practice
+            .table()
+            .rows
+            .iter()
+            .map(|r| *r.get_left())
+            .collect();
+        assert_eq!(
+            Practice::deal(Deck::new(52).unwrap(), 3, 11)
+                .unwrap()
+                .table()
+                .rows
+                .iter()
+                .map(|r| *r.get_left())
+                .collect::<Vec<Card>>(),
+            cards
+        );
+        assert_eq!(
+            Practice::deal(Deck::new(8).unwrap(), 0, 1).err(),
+            Some(Error::NoRows)
+        );
+        assert_eq!(
+            Practice::deal(Deck::new(8).unwrap(), 9, 1).err(),
+            Some(Error::DeckEmpty)
+        );
+    }
+
+    #[test]
+    fn guesses_apply_to_the_row() {
+        let mut practice = practice();
+        for _ in 0..30 {
+            let before = practice.table().rows[1].len();
+            let size = practice.deck().size();
+            let round = practice.guess(1, Side::Right, Guess::Higher).unwrap();
+            let row = &practice.table().rows[1];
+            match round.outcome {
+                Outcome::Win => {
+                    assert_eq!(row.len(), before + 1);
+                    assert_eq!(*row.get_right(), round.card);
+                }
+                Outcome::Push => assert_eq!(row.len(), before),
+                Outcome::Lose => {
+                    assert_eq!(row.cards(), &[round.card]);
+                    assert_eq!(practice.deck().size(), size + before - 1);
+                }
+            }
+        }
+        let score = practice.score();
+        assert_eq!(score.rounds, 30);
+        assert!(score.wins > 0 && score.wins < 30);
+        assert_eq!(
+            practice.guess(3, Side::Left, Guess::Lower),
+            Err(Error::RowNotFound(3))
+        );
+    }
+
+    #[test]
+    fn practice_follows_its_rules() {
+        let mut practice = practice();
+        practice.set_rules(Box::new(TargetLength { length: 2 }));
+        assert_eq!(practice.rules().name(), "target-2");
+        while practice.table().rows[0].len() < 2 {
+            practice.guess(0, Side::Right, Guess::Higher).unwrap();
+        }
+        assert_eq!(
+            practice.guess(0, Side::Left, Guess::Lower),
+            Err(Error::GuessNotAllowed {
+                row: 0,
+                side: Side::Left
+            })
+        );
+        assert_eq!(practice.rules().score(practice.table()), Some(1));
+    }
+
+    #[test]
+    fn rounds_compare_with_the_odds() {
+        let mut round = Round {
+            row: 0,
+            side: Side::Left,
+            guess: Guess::Lower,
+            card: card("c5"),
+            outcome: Outcome::Lose,
+            chance: Ratio::new(1, 4),
+            best: Guess::Higher,
+            best_chance: Ratio::new(7, 10),
+        };
+        assert!(!round.matched_odds());
+        assert_eq!(
+            round.describe(Style::plain()),
+            vec![
+                "Drew [♥  5]: lower loses, row 1 collapses",
+                "The odds favored higher (0.70 against 0.25)"
+            ]
+        );
+        round.guess = Guess::Higher;
+        round.chance = Ratio::new(7, 10);
+        round.outcome = Outcome::Win;
+        assert!(round.matched_odds());
+        assert_eq!(
+            round.describe(Style::plain())[1],
+            "Good call: higher had the better odds (0.70)"
+        );
+    }
+
+    #[test]
+    fn lines_mark_the_selected_end() {
+        let (deck, table) = deal(8, &["a13", "b14"]);
+        let practice = Practice::new(deck, table, 0);
+        let style = Style {
+            ascii: true,
+            ..Style::plain()
+        };
+        assert_eq!(
+            practice.lines(1, Side::Right, style),
+            vec!["1:   [C  K]", "2:   [S  A] >"]
+        );
+        assert_eq!(
+            practice.lines(0, Side::Left, Style::plain()),
+            vec!["1: ◀ [♣  K]", "2:   [♠  A]"]
+        );
+    }
+}
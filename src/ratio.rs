@@ -0,0 +1,169 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Exact rational number, always stored in lowest terms with a positive
+/// denominator.
+///
+/// Arithmetic panics on overflow rather than silently losing precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    num: i128,
+    den: i128,
+}
+
+impl Ratio {
+    pub const ZERO: Ratio = Ratio { num: 0, den: 1 };
+    pub const ONE: Ratio = Ratio { num: 1, den: 1 };
+
+    /// Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Ratio {
+        assert!(den != 0, "ratio with zero denominator");
+        let sign = if den < 0 { -1 } else { 1 };
+        let divisor = gcd(num, den).max(1);
+        Ratio {
+            num: sign * num / divisor,
+            den: sign * den / divisor,
+        }
+    }
+
+    pub fn from_integer(n: i128) -> Ratio {
+        Ratio { num: n, den: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        self.num as f32 / self.den as f32
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn checked(value: Option<i128>) -> i128 {
+    value.expect("ratio arithmetic overflow")
+}
+
+impl Add for Ratio {
+    type Output = Ratio;
+
+    fn add(self, other: Ratio) -> Ratio {
+        let divisor = gcd(self.den, other.den);
+        let left = checked(self.num.checked_mul(other.den / divisor));
+        let right = checked(other.num.checked_mul(self.den / divisor));
+        Ratio::new(
+            checked(left.checked_add(right)),
+            checked(self.den.checked_mul(other.den / divisor)),
+        )
+    }
+}
+
+impl Neg for Ratio {
+    type Output = Ratio;
+
+    fn neg(self) -> Ratio {
+        Ratio {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Sub for Ratio {
+    type Output = Ratio;
+
+    fn sub(self, other: Ratio) -> Ratio {
+        self + -other
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Ratio;
+
+    fn mul(self, other: Ratio) -> Ratio {
+        let a = gcd(self.num, other.den).max(1);
+        let b = gcd(other.num, self.den).max(1);
+        Ratio::new(
+            checked((self.num / a).checked_mul(other.num / b)),
+            checked((self.den / b).checked_mul(other.den / a)),
+        )
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (*self - *other).num.cmp(&0)
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 {
+            return write!(f, "{}", self.num);
+        }
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ratio_is_reduced() {
+        let ratio = Ratio::new(6, -8);
+        assert_eq!((ratio.numer(), ratio.denom()), (-3, 4));
+        assert_eq!(Ratio::new(0, 5), Ratio::ZERO);
+        assert_eq!(Ratio::new(4, 4), Ratio::ONE);
+    }
+
+    #[test]
+    fn ratio_arithmetic_is_exact() {
+        let third = Ratio::new(1, 3);
+        let sixth = Ratio::new(1, 6);
+        assert_eq!(third + sixth, Ratio::new(1, 2));
+        assert_eq!(third - sixth, sixth);
+        assert_eq!(third * Ratio::new(3, 4), Ratio::new(1, 4));
+        assert_eq!(-third + third, Ratio::ZERO);
+        let tenth = Ratio::new(1, 10);
+        let sum = (0..10).fold(Ratio::ZERO, |acc, _| acc + tenth);
+        assert_eq!(sum, Ratio::ONE);
+    }
+
+    #[test]
+    fn ratio_is_ordered() {
+        assert!(Ratio::new(2, 7) < Ratio::new(1, 3));
+        assert!(Ratio::new(-1, 2) < Ratio::ZERO);
+        assert_eq!(Ratio::new(3, 9).max(Ratio::new(1, 4)), Ratio::new(1, 3));
+    }
+
+    #[test]
+    fn ratio_converts_to_float() {
+        assert_eq!(Ratio::new(3, 7).to_f32(), 3.0 / 7.0);
+        assert_eq!(Ratio::new(1, 4).to_f64(), 0.25);
+        assert_eq!(Ratio::new(3, 7).to_string(), "3/7");
+        assert_eq!(Ratio::from_integer(-2).to_string(), "-2");
+    }
+}
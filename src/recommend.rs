@@ -0,0 +1,89 @@
+use std::cmp::Reverse;
+
+use crate::{Deck, Guess, Ratio, Side, Table};
+
+/// Suggested guess for one end of a row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Recommendation {
+    pub row: usize,
+    pub side: Side,
+    pub guess: Guess,
+    /// Exact chance of the guess winning.
+    pub chance: Ratio,
+}
+
+/// Recommendations for both ends of every row, best move first.
+///
+/// Moves with the same chance keep table order, left before right.
+pub fn recommend(deck: &Deck, table: &Table) -> Vec<Recommendation> {
+    let mut recommendations = Vec::new();
+    for (row, cards) in table.rows.iter().enumerate() {
+        for side in [Side::Left, Side::Right] {
+            let card = cards.get(side);
+            let guess = deck.recommend(card);
+            recommendations.push(Recommendation {
+                row,
+                side,
+                guess,
+                chance: deck.chance(card, guess),
+            });
+        }
+    }
+    recommendations.sort_by_key(|r| Reverse(r.chance));
+    recommendations
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::{card, deal};
+    use crate::TieRule;
+
+    #[test]
+    fn recommendations_cover_every_row_end() {
+        let (deck, table) = deal(52, &["a2", "b8", "c14"]);
+        let recommendations = recommend(&deck, &table);
+        assert_eq!(recommendations.len(), 6);
+        for row in 0..3 {
+            for side in [Side::Left, Side::Right] {
+                assert!(recommendations
+                    .iter()
+                    .any(|r| r.row == row && r.side == side));
+            }
+        }
+    }
+
+    #[test]
+    fn recommendations_are_ranked_by_chance() {
+        let (mut deck, mut table) = deal(52, &["b8", "a2", "c13"]);
+        deck.remove(&card("d3")).unwrap();
+        table.rows[2].add_left(card("d3"));
+        let recommendations = recommend(&deck, &table);
+        let best = recommendations[0];
+        assert_eq!((best.row, best.side), (1, Side::Left));
+        assert_eq!(best.guess, Guess::Higher);
+        assert_eq!(best.chance, Ratio::new(45, 48));
+        assert_eq!(recommendations[1].row, 1);
+        let third = recommendations[2];
+        assert_eq!((third.row, third.side), (2, Side::Left));
+        assert_eq!(third.guess, Guess::Higher);
+        assert_eq!(recommendations[3].guess, Guess::Lower);
+        for pair in recommendations.windows(2) {
+            assert!(pair[0].chance >= pair[1].chance);
+        }
+    }
+
+    #[test]
+    fn recommendations_follow_tie_rule() {
+        let (mut deck, table) = deal(8, &["a13"]);
+        deck.remove(&card("b14")).unwrap();
+        deck.remove(&card("c14")).unwrap();
+        let best = recommend(&deck, &table)[0];
+        assert_eq!(best.guess, Guess::Higher);
+        assert_eq!(best.chance, Ratio::new(2, 5));
+        deck.set_tie_rule(TieRule::HigherOrEqual);
+        let best = recommend(&deck, &table)[0];
+        assert_eq!(best.guess, Guess::Higher);
+        assert_eq!(best.chance, Ratio::ONE);
+    }
+}
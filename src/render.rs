@@ -0,0 +1,156 @@
+//! Flicker-free drawing of full-screen frames.
+//!
+//! A frame is a list of screen lines that may contain escape sequences for
+//! colors and styles, but no cursor movement.
+
+use std::io::{self, Write};
+
+use termion::{clear, cursor};
+
+/// Draws frames of screen lines, rewriting only the lines that changed
+/// since the previous frame.
+pub struct Renderer {
+    lines: Vec<String>,
+    size: (u16, u16),
+    full: bool,
+}
+
+impl Renderer {
+    /// Renderer for a terminal of `(columns, rows)`; the first frame is drawn
+    /// in full.
+    pub fn new(size: (u16, u16)) -> Renderer {
+        Renderer {
+            lines: Vec::new(),
+            size,
+            full: true,
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    /// Redraws the next frame in full at the new terminal size.
+    pub fn resize(&mut self, size: (u16, u16)) {
+        self.size = size;
+        self.full = true;
+    }
+
+    /// Draws the frame, dropping lines below the bottom of the terminal.
+    pub fn draw<W: Write>(&mut self, out: &mut W, frame: &[String]) -> io::Result<()> {
+        let frame = &frame[..frame.len().min(self.size.1 as usize)];
+        if self.full {
+            write!(out, "{}", clear::All)?;
+            self.lines.clear();
+        }
+        for (i, line) in frame.iter().enumerate() {
+            if !self.full && self.lines.get(i) == Some(line) {
+                continue;
+            }
+            write!(
+                out,
+                "{}{}{}",
+                cursor::Goto(1, i as u16 + 1),
+                clear::CurrentLine,
+                line
+            )?;
+        }
+        for i in frame.len()..self.lines.len() {
+            write!(
+                out,
+                "{}{}",
+                cursor::Goto(1, i as u16 + 1),
+                clear::CurrentLine
+            )?;
+        }
+        self.lines = frame.to_vec();
+        self.full = false;
+        out.flush()
+    }
+}
+
+/// Number of terminal columns the line takes up, ignoring escape sequences.
+pub fn width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            width += 1;
+            continue;
+        }
+        // Skip a control sequence up to its final byte.
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use termion::color;
+
+    fn frame(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    fn draw(renderer: &mut Renderer, lines: &[&str]) -> String {
+        let mut out = Vec::new();
+        renderer.draw(&mut out, &frame(lines)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn renderer_rewrites_changed_lines_only() {
+        let mut renderer = Renderer::new((80, 24));
+        let first = draw(&mut renderer, &["one", "two", "three"]);
+        assert!(first.starts_with(&clear::All.to_string()));
+        assert!(first.contains("one") && first.contains("three"));
+        let second = draw(&mut renderer, &["one", "2", "three"]);
+        assert_eq!(
+            second,
+            format!("{}{}2", cursor::Goto(1, 2), clear::CurrentLine)
+        );
+        assert_eq!(draw(&mut renderer, &["one", "2", "three"]), "");
+    }
+
+    #[test]
+    fn renderer_clears_removed_lines() {
+        let mut renderer = Renderer::new((80, 24));
+        draw(&mut renderer, &["one", "two", "three"]);
+        assert_eq!(
+            draw(&mut renderer, &["one"]),
+            format!(
+                "{}{}{}{}",
+                cursor::Goto(1, 2),
+                clear::CurrentLine,
+                cursor::Goto(1, 3),
+                clear::CurrentLine
+            )
+        );
+    }
+
+    #[test]
+    fn renderer_redraws_after_resize() {
+        let mut renderer = Renderer::new((80, 24));
+        draw(&mut renderer, &["one", "two", "three"]);
+        renderer.resize((40, 2));
+        let out = draw(&mut renderer, &["one", "two", "three"]);
+        assert!(out.starts_with(&clear::All.to_string()));
+        assert!(out.contains("two"));
+        assert!(!out.contains("three"));
+    }
+
+    #[test]
+    fn width_ignores_escape_sequences() {
+        assert_eq!(width("[♥  Q]"), 6);
+        let red = format!("{}[♥  Q]{}", color::Fg(color::Red), color::Fg(color::Reset));
+        assert_eq!(width(&red), 6);
+        assert_eq!(width(&format!("{}", clear::CurrentLine)), 0);
+    }
+}
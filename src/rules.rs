@@ -0,0 +1,120 @@
+use crate::{Card, Error, Guess, Outcome, Row, Side, Table, TieRule};
+
+/// A variant of the game: which guesses are allowed, whether a guess
+/// succeeds, when a row is complete and how the table scores.
+///
+/// Only guesses go through the rules; raw commands stay available for
+/// corrections.
+pub trait Rules {
+    /// Name accepted by [`parse`].
+    fn name(&self) -> String;
+
+    /// Whether a guess may be placed on this end of the row.
+    fn allows(&self, row: &Row, _side: Side) -> bool {
+        !self.is_complete(row)
+    }
+
+    /// Whether the guess against the row end wins, ties or loses.
+    fn outcome(&self, tie_rule: TieRule, guess: Guess, base: &Card, drawn: &Card) -> Outcome {
+        tie_rule.outcome(guess, base.rank, drawn.rank)
+    }
+
+    /// Whether the row is finished and takes no more guesses.
+    fn is_complete(&self, _row: &Row) -> bool {
+        false
+    }
+
+    /// Points for the table, or `None` if the variant keeps no score.
+    fn score(&self, _table: &Table) -> Option<u32> {
+        None
+    }
+
+    /// The score as shown to the player, if the variant keeps one.
+    fn score_line(&self, table: &Table) -> Option<String> {
+        let score = self.score(table)?;
+        Some(format!("Score under {} rules: {}", self.name(), score))
+    }
+}
+
+/// Rows grow without limit and nothing is scored.
+pub struct Classic;
+
+impl Rules for Classic {
+    fn name(&self) -> String {
+        String::from("classic")
+    }
+}
+
+/// Rows are complete once they reach `length` cards, scoring a point each.
+pub struct TargetLength {
+    pub length: usize,
+}
+
+impl Rules for TargetLength {
+    fn name(&self) -> String {
+        format!("target-{}", self.length)
+    }
+
+    fn is_complete(&self, row: &Row) -> bool {
+        row.len() >= self.length
+    }
+
+    fn score(&self, table: &Table) -> Option<u32> {
+        Some(table.rows.iter().filter(|r| self.is_complete(r)).count() as u32)
+    }
+}
+
+/// The rules named `classic` or `target-N`, for rows of at least two cards.
+pub fn parse(name: &str) -> Result<Box<dyn Rules>, Error> {
+    if name == "classic" {
+        return Ok(Box::new(Classic));
+    }
+    match name.strip_prefix("target-").map(|n| n.parse::<usize>()) {
+        Some(Ok(length)) if length >= 2 => Ok(Box::new(TargetLength { length })),
+        _ => Err(Error::UnknownRules(name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::card;
+
+    #[test]
+    fn rules_can_be_parsed() {
+        assert_eq!(parse("classic").unwrap().name(), "classic");
+        assert_eq!(parse("target-5").unwrap().name(), "target-5");
+        for name in ["target-1", "target-", "target-x", "pyramid"] {
+            assert_eq!(
+                parse(name).err(),
+                Some(Error::UnknownRules(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn classic_never_completes() {
+        let table = Table::from_rows(vec![vec![card("a2"); 8]]).unwrap();
+        assert!(!Classic.is_complete(&table.rows[0]));
+        assert!(Classic.allows(&table.rows[0], Side::Left));
+        assert_eq!(Classic.score(&table), None);
+        assert_eq!(
+            Classic.outcome(TieRule::Push, Guess::Higher, &card("a2"), &card("b2")),
+            Outcome::Push
+        );
+    }
+
+    #[test]
+    fn target_length_scores_complete_rows() {
+        let rules = TargetLength { length: 3 };
+        let table = Table::from_rows(vec![
+            vec![card("a2"), card("a3"), card("a4")],
+            vec![card("b2"), card("b3")],
+            vec![card("c2"), card("c3"), card("c4"), card("c5")],
+        ])
+        .unwrap();
+        assert!(!rules.allows(&table.rows[0], Side::Right));
+        assert!(rules.allows(&table.rows[1], Side::Left));
+        assert_eq!(rules.score(&table), Some(2));
+    }
+}
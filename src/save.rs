@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::{rules, Card, DeckSpec, Error, Event, Game, Table, TieRule};
+
+/// On-disk form of a game, stored as pretty-printed JSON.
+///
+/// The game is rebuilt by replaying `history` on top of `initial`, so a
+/// session only loads if every recorded event is still valid. `rows` is
+/// written for readers and checked against the replayed table.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    deck: DeckSpec,
+    tie_rule: TieRule,
+    #[serde(default = "classic")]
+    rules: String,
+    initial: Vec<Vec<Card>>,
+    rows: Vec<Vec<Card>>,
+    history: Vec<Event>,
+    position: usize,
+}
+
+fn classic() -> String {
+    String::from("classic")
+}
+
+impl Game {
+    pub fn to_json(&self) -> String {
+        let session = Session {
+            deck: self.deck().spec().clone(),
+            tie_rule: self.deck().tie_rule(),
+            rules: self.rules().name(),
+            initial: self.initial().to_vec(),
+            rows: self
+                .table()
+                .rows
+                .iter()
+                .map(|r| r.cards().to_vec())
+                .collect(),
+            history: self.history().to_vec(),
+            position: self.position(),
+        };
+        serde_json::to_string_pretty(&session).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Game, Error> {
+        let session: Session =
+            serde_json::from_str(json).map_err(|e| Error::InvalidSave(e.to_string()))?;
+        let mut deck = session.deck.build()?;
+        deck.set_tie_rule(session.tie_rule);
+        for card in session.initial.iter().flatten() {
+            deck.remove(card)?;
+        }
+        let table = Table::from_rows(session.initial).map_err(|e| match e {
+            Error::RowEmpty(row) => Error::InvalidSave(format!("empty row {}", row + 1)),
+            e => e,
+        })?;
+        let mut game = Game::new(deck, table);
+        game.set_rules(rules::parse(&session.rules)?);
+        for event in session.history.iter() {
+            game.apply(event.row, event.command, Some(event.card))?;
+        }
+        if game.history() != session.history.as_slice() {
+            return Err(Error::InvalidSave(String::from(
+                "history does not match the table",
+            )));
+        }
+        game.seek(session.position)?;
+        if game
+            .table()
+            .rows
+            .iter()
+            .map(|r| r.cards())
+            .ne(session.rows.iter().map(|r| r.as_slice()))
+        {
+            return Err(Error::InvalidSave(String::from(
+                "rows do not match the history",
+            )));
+        }
+        Ok(game)
+    }
+
+    /// Writes the game to `path`, replacing the file only once the new
+    /// contents are complete.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, self.to_json()).map_err(|e| Error::Io(e.to_string()))?;
+        fs::rename(&tmp, path).map_err(|e| Error::Io(e.to_string()))
+    }
+
+    pub fn load(path: &Path) -> Result<Game, Error> {
+        let json = fs::read_to_string(path).map_err(|e| Error::Io(e.to_string()))?;
+        Game::from_json(&json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::card;
+    use crate::{Command, Rank};
+
+    fn game() -> Game {
+        let mut deck = DeckSpec::new()
+            .decks(2)
+            .lowest(Rank::new(9).unwrap())
+            .jokers(1)
+            .build()
+            .unwrap();
+        deck.set_tie_rule(TieRule::Push);
+        let cards = vec![card("a14"), card("b11")];
+        for c in cards.iter() {
+            deck.remove(c).unwrap();
+        }
+        let mut game = Game::new(deck, Table::new(2, cards).unwrap());
+        game.apply(0, Command::AddRight, Some(card("c13"))).unwrap();
+        game.apply(0, Command::Collapse, Some(card("jk"))).unwrap();
+        game.apply(1, Command::AddLeft, Some(card("a14"))).unwrap();
+        game.apply(1, Command::RemoveRight, None).unwrap();
+        game.undo().unwrap();
+        game.set_rules(rules::parse("target-4").unwrap());
+        game
+    }
+
+    fn rows(game: &Game) -> Vec<Vec<Card>> {
+        game.table()
+            .rows
+            .iter()
+            .map(|r| r.cards().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn game_round_trips_through_json() {
+        let game = game();
+        let loaded = Game::from_json(&game.to_json()).unwrap();
+        assert_eq!(rows(&loaded), rows(&game));
+        assert_eq!(loaded.history(), game.history());
+        assert_eq!(loaded.position(), 3);
+        assert_eq!(loaded.deck().size(), game.deck().size());
+        assert_eq!(loaded.deck().spec(), game.deck().spec());
+        assert_eq!(loaded.deck().tie_rule(), TieRule::Push);
+        assert_eq!(loaded.deck().count(&card("a14")), 1);
+        assert_eq!(loaded.rules().name(), "target-4");
+        let classic = game.to_json().replace("  \"rules\": \"target-4\",\n", "");
+        assert_eq!(Game::from_json(&classic).unwrap().rules().name(), "classic");
+    }
+
+    #[test]
+    fn session_is_human_readable() {
+        let json = game().to_json();
+        assert!(json.contains("\"tie_rule\": \"push\""));
+        assert!(json.contains("\"command\": \"add-right\""));
+        assert!(json.contains("\"c13\""));
+    }
+
+    #[test]
+    fn corrupted_sessions_are_rejected() {
+        let json = game().to_json();
+        assert!(matches!(Game::from_json("{"), Err(Error::InvalidSave(_))));
+        let tampered = json.replacen("\"b11\"", "\"b12\"", 1);
+        assert!(Game::from_json(&tampered).is_err());
+        let tampered = json.replace("\"position\": 3", "\"position\": 9");
+        assert_eq!(
+            Game::from_json(&tampered).err(),
+            Some(Error::HistoryOutOfRange(9))
+        );
+        let mut empty: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for field in ["initial", "rows", "history"] {
+            empty[field] = serde_json::json!([]);
+        }
+        empty["position"] = serde_json::json!(0);
+        assert_eq!(
+            Game::from_json(&empty.to_string()).err(),
+            Some(Error::NoRows)
+        );
+        let mut empty_row: serde_json::Value = serde_json::from_str(&json).unwrap();
+        empty_row["initial"][1] = serde_json::json!([]);
+        assert_eq!(
+            Game::from_json(&empty_row.to_string()).err(),
+            Some(Error::InvalidSave(String::from("empty row 2")))
+        );
+    }
+
+    #[test]
+    fn game_can_be_saved_to_file() {
+        let dir = std::env::temp_dir().join(format!("hilo-save-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+        let game = game();
+        game.save(&path).unwrap();
+        assert_eq!(rows(&Game::load(&path).unwrap()), rows(&game));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(Game::load(&path), Err(Error::Io(_))));
+    }
+}
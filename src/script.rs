@@ -0,0 +1,232 @@
+//! Line-oriented move scripts for replaying games without the TUI.
+//!
+//! Each line holds a one-based row number, a command and, for commands that
+//! draw a card, the card in any notation [`Card`] accepts: `2 al c5`,
+//! `1 c 9d`, `2 ar queen of hearts`, `3 dr`. Blank lines and lines
+//! starting with `#` are skipped.
+//!
+//! Instead of a command, a line may hold a guess on one end of the row,
+//! `hl`, `hr`, `ll` or `lr` for higher or lower on the left or right, and
+//! the drawn card decides the command: `2 hr d9`.
+
+use std::str::FromStr;
+
+use crate::{Card, Command, Error, Game, Guess, Side, Style};
+
+/// What a move does to its row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Command(Command),
+    /// A guess on one end, extending or collapsing the row by the drawn card.
+    Guess(Side, Guess),
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Action, Error> {
+        let guess = match s {
+            "hl" => (Side::Left, Guess::Higher),
+            "hr" => (Side::Right, Guess::Higher),
+            "ll" => (Side::Left, Guess::Lower),
+            "lr" => (Side::Right, Guess::Lower),
+            _ => return s.parse().map(Action::Command),
+        };
+        Ok(Action::Guess(guess.0, guess.1))
+    }
+}
+
+/// A single parsed script line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub row: usize,
+    pub action: Action,
+    pub card: Option<Card>,
+}
+
+impl Move {
+    pub fn apply(&self, game: &mut Game) -> Result<(), Error> {
+        match (self.action, self.card) {
+            (Action::Command(command), card) => game.apply(self.row, command, card),
+            (Action::Guess(side, guess), Some(card)) => {
+                game.guess(self.row, side, guess, card).map(|_| ())
+            }
+            (Action::Guess(..), None) => Err(Error::CardRequired),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Move, Error> {
+        let invalid = || Error::InvalidMove(s.to_string());
+        let mut words = s.split_whitespace();
+        let row = match words.next().map(|w| w.parse::<usize>()) {
+            Some(Ok(row)) if row > 0 => row - 1,
+            _ => return Err(invalid()),
+        };
+        let action: Action = words.next().ok_or_else(invalid)?.parse()?;
+        let card = words.collect::<Vec<&str>>().join(" ");
+        let card = match card.is_empty() {
+            true => None,
+            false => Some(card.parse()?),
+        };
+        let needs_card = !matches!(
+            action,
+            Action::Command(Command::RemoveLeft | Command::RemoveRight)
+        );
+        if card.is_some() != needs_card {
+            return Err(invalid());
+        }
+        Ok(Move { row, action, card })
+    }
+}
+
+/// Applies every move of the script, stopping at the first invalid line.
+///
+/// Moves before the invalid line stay applied.
+pub fn run(game: &mut Game, script: &str) -> Result<(), Error> {
+    for (i, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        line.parse::<Move>()
+            .and_then(|m| m.apply(game))
+            .map_err(|e| Error::Line {
+                line: i + 1,
+                error: Box::new(e),
+            })?;
+    }
+    Ok(())
+}
+
+/// The table with the odds of both ends of every row and the best move.
+pub fn summary(game: &Game, style: Style) -> String {
+    let deck = game.deck();
+    let mut lines = Vec::new();
+    for (i, row) in game.table().rows.iter().enumerate() {
+        lines.push(format!(
+            "{}: {}  left {}  right {}",
+            i + 1,
+            row.format(style),
+            deck.format_card_chance(row.get_left(), style),
+            deck.format_card_chance(row.get_right(), style),
+        ));
+    }
+    lines.extend(game.status_lines());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::{card, deal};
+
+    fn game() -> Game {
+        let (deck, table) = deal(52, &["a14", "b3", "c7"]);
+        Game::new(deck, table)
+    }
+
+    #[test]
+    fn moves_can_be_parsed() {
+        assert_eq!(
+            "2 al c5".parse(),
+            Ok(Move {
+                row: 1,
+                action: Action::Command(Command::AddLeft),
+                card: Some(card("c5")),
+            })
+        );
+        assert_eq!(
+            " 3  dr ".parse(),
+            Ok(Move {
+                row: 2,
+                action: Action::Command(Command::RemoveRight),
+                card: None,
+            })
+        );
+        assert_eq!(
+            "1 ar queen of hearts".parse::<Move>().map(|m| m.card),
+            Ok(Some(card("c12")))
+        );
+        assert_eq!(
+            "2 hr d9".parse(),
+            Ok(Move {
+                row: 1,
+                action: Action::Guess(Side::Right, Guess::Higher),
+                card: Some(card("d9")),
+            })
+        );
+        for line in ["0 al c5", "x al c5", "1 c", "1 dl a2", "1", "1 ll"] {
+            assert_eq!(
+                line.parse::<Move>(),
+                Err(Error::InvalidMove(line.to_string()))
+            );
+        }
+        assert_eq!(
+            "1 al a2 a3".parse::<Move>(),
+            Err(Error::UnknownCard(String::from("a2 a3")))
+        );
+        assert_eq!(
+            "1 up a2".parse::<Move>(),
+            Err(Error::UnknownCommand(String::from("up")))
+        );
+    }
+
+    #[test]
+    fn script_is_applied() {
+        let mut game = game();
+        run(&mut game, "# opening\n2 al c5\n\n1 c d9\n3 ar a8\n3 dr\n").unwrap();
+        assert_eq!(game.table().rows[0].cards(), &[card("d9")]);
+        assert_eq!(game.table().rows[1].cards(), &[card("c5"), card("b3")]);
+        assert_eq!(game.table().rows[2].cards(), &[card("c7")]);
+        assert_eq!(game.history().len(), 4);
+        assert_eq!(game.deck().size(), 48);
+    }
+
+    #[test]
+    fn script_applies_guesses() {
+        let mut game = game();
+        run(&mut game, "2 hr c5\n1 ll d9\n3 hl a6\n1 x b2\n").unwrap();
+        assert_eq!(game.table().rows[0].cards(), &[card("d9"), card("a14")]);
+        assert_eq!(game.table().rows[1].cards(), &[card("b3"), card("c5")]);
+        assert_eq!(game.table().rows[2].cards(), &[card("a6")]);
+        assert_eq!(game.deck().size(), 46);
+    }
+
+    #[test]
+    fn script_fails_on_first_invalid_line() {
+        let mut game = game();
+        assert_eq!(
+            run(&mut game, "2 al c5\n\n1 al c5\n1 al c6\n"),
+            Err(Error::Line {
+                line: 3,
+                error: Box::new(Error::CardAlreadyRemoved(card("c5"))),
+            })
+        );
+        assert_eq!(game.history().len(), 1);
+        assert_eq!(
+            run(&mut game, "4 al c6").unwrap_err().to_string(),
+            "Line 1: Row 4 does not exist"
+        );
+    }
+
+    #[test]
+    fn summary_is_plain_text() {
+        let mut game = game();
+        run(&mut game, "2 ar c5").unwrap();
+        let summary = summary(&game, Style::plain());
+        assert!(!summary.contains('\x1b'));
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("2: [♠  3] [♥  5]  left »▲ 0.85"));
+        assert!(lines[3].starts_with("Best move: row 1"));
+        game.set_rules(crate::rules::parse("target-2").unwrap());
+        let scored = super::summary(&game, Style::plain());
+        let lines: Vec<&str> = scored.lines().collect();
+        assert_eq!(lines[3], "Score under target-2 rules: 1");
+        assert!(!lines[4].starts_with("Best move: row 2"));
+    }
+}
@@ -0,0 +1,139 @@
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Card, Command, Deck, Error, Outcome, Row, Side};
+
+/// Shuffled draw pile of the cards left in a deck.
+///
+/// The same seed and deck always deal the same cards, including after
+/// reshuffles.
+pub struct Shoe {
+    pile: Vec<Card>,
+    rng: ChaCha8Rng,
+}
+
+impl Shoe {
+    pub fn new(deck: &Deck, seed: u64) -> Shoe {
+        let mut shoe = Shoe {
+            pile: Vec::new(),
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        shoe.reshuffle(deck);
+        shoe
+    }
+
+    /// Number of cards left in the pile.
+    pub fn len(&self) -> usize {
+        self.pile.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pile.is_empty()
+    }
+
+    /// Takes the top card off the pile and removes it from the deck.
+    pub fn draw(&mut self, deck: &mut Deck) -> Result<Card, Error> {
+        let card = *self.pile.last().ok_or(Error::DeckEmpty)?;
+        deck.remove(&card)?;
+        self.pile.pop();
+        Ok(card)
+    }
+
+    /// Shuffles every card left in the deck into a new pile.
+    pub fn reshuffle(&mut self, deck: &Deck) {
+        self.pile = deck.cards();
+        self.pile.shuffle(&mut self.rng);
+    }
+
+    /// Changes the row by the outcome of a guess on the drawn card like
+    /// [`Row::resolve`], reshuffling after a collapse so the row's cards can
+    /// be drawn again.
+    pub fn resolve(
+        &mut self,
+        row: &mut Row,
+        side: Side,
+        outcome: Outcome,
+        card: Card,
+        deck: &mut Deck,
+    ) -> Result<Command, Error> {
+        let command = row.resolve(side, outcome, card, deck)?;
+        if command == Command::Collapse {
+            self.reshuffle(deck);
+        }
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Table;
+
+    fn sorted(mut cards: Vec<Card>) -> Vec<Card> {
+        cards.sort_by_key(|c| (c.rank, c.suit));
+        cards
+    }
+
+    #[test]
+    fn shoe_deals_every_card_once() {
+        let mut deck = Deck::new(8).unwrap();
+        let mut shoe = Shoe::new(&deck, 1);
+        assert_eq!(shoe.len(), 8);
+        let drawn: Vec<Card> = (0..8).map(|_| shoe.draw(&mut deck).unwrap()).collect();
+        assert_eq!(shoe.draw(&mut deck), Err(Error::DeckEmpty));
+        assert!(shoe.is_empty());
+        assert_eq!(deck.size(), 0);
+        assert_eq!(sorted(drawn), sorted(deck.spec().cards()));
+    }
+
+    #[test]
+    fn shoe_is_seeded() {
+        let deal = |seed| {
+            let mut deck = Deck::new(52).unwrap();
+            let mut shoe = Shoe::new(&deck, seed);
+            (0..10)
+                .map(|_| shoe.draw(&mut deck).unwrap())
+                .collect::<Vec<Card>>()
+        };
+        assert_eq!(deal(5), deal(5));
+        assert_ne!(deal(5), deal(6));
+    }
+
+    #[test]
+    fn shoe_reshuffles_on_collapse() {
+        let mut deck = Deck::new(8).unwrap();
+        let mut shoe = Shoe::new(&deck, 2);
+        let first = shoe.draw(&mut deck).unwrap();
+        let mut table = Table::new(1, vec![first]).unwrap();
+        let second = shoe.draw(&mut deck).unwrap();
+        table.rows[0].add_right(second);
+        let third = shoe.draw(&mut deck).unwrap();
+        let resolved = shoe.resolve(
+            &mut table.rows[0],
+            Side::Left,
+            Outcome::Lose,
+            third,
+            &mut deck,
+        );
+        assert_eq!(resolved, Ok(Command::Collapse));
+        assert_eq!(table.rows[0].cards(), &[third]);
+        assert_eq!(shoe.len(), 7);
+        assert_eq!(deck.size(), 7);
+        let rest: Vec<Card> = (0..7).map(|_| shoe.draw(&mut deck).unwrap()).collect();
+        assert!(rest.contains(&first) && rest.contains(&second));
+        assert!(!rest.contains(&third));
+    }
+
+    #[test]
+    fn shoe_rejects_cards_drawn_elsewhere() {
+        let mut deck = Deck::new(8).unwrap();
+        let mut shoe = Shoe::new(&deck, 3);
+        let top = *shoe.pile.last().unwrap();
+        deck.remove(&top).unwrap();
+        assert_eq!(shoe.draw(&mut deck), Err(Error::CardAlreadyRemoved(top)));
+        assert_eq!(shoe.len(), 8);
+        shoe.reshuffle(&deck);
+        assert_eq!(shoe.len(), 7);
+    }
+}
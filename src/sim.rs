@@ -0,0 +1,401 @@
+//! Monte Carlo simulation of whole games under different strategies.
+//!
+//! A simulated game deals one card per row from a [`Shoe`]. Each turn
+//! the strategy bets on one end of a row, then a card is drawn and changes
+//! the row as [`Command::from_outcome`]; a collapse shuffles the row's
+//! cards back into the shoe. The game is won once a row is complete under
+//! the [`Rules`] and lost after running out of lives or cards.
+//!
+//! Games are dealt from a seeded RNG, so runs are reproducible. Strategies
+//! draw from a separate RNG, so every strategy is dealt the same cards.
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::rules::{Rules, TargetLength};
+use crate::{recommend, Card, Command, Deck, DeckSpec, Error, Guess, Shoe, Side, Table, TieRule};
+
+/// A bet on one end of a row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bet {
+    pub row: usize,
+    pub side: Side,
+    pub guess: Guess,
+}
+
+/// A way of choosing the next bet.
+pub trait Strategy {
+    fn name(&self) -> String;
+
+    /// The bet to place before the next card is drawn from the deck.
+    fn choose(&mut self, deck: &Deck, table: &Table, rng: &mut dyn RngCore) -> Bet;
+}
+
+/// Always takes the bet with the best chance of winning.
+pub struct BestOdds;
+
+impl Strategy for BestOdds {
+    fn name(&self) -> String {
+        String::from("best-odds")
+    }
+
+    fn choose(&mut self, deck: &Deck, table: &Table, _rng: &mut dyn RngCore) -> Bet {
+        best(deck, table)
+    }
+}
+
+/// Keeps building the longest row as long as its best bet wins with at
+/// least `chance`, otherwise takes the best bet anywhere.
+pub struct Threshold {
+    pub chance: f32,
+}
+
+impl Strategy for Threshold {
+    fn name(&self) -> String {
+        format!("threshold-{:.2}", self.chance)
+    }
+
+    fn choose(&mut self, deck: &Deck, table: &Table, _rng: &mut dyn RngCore) -> Bet {
+        let mut choice = None;
+        for r in recommend(deck, table) {
+            let len = table.rows[r.row].len();
+            if r.chance.to_f32() >= self.chance && choice.is_none_or(|(_, best)| len > best) {
+                choice = Some((r, len));
+            }
+        }
+        match choice {
+            Some((r, _)) => Bet {
+                row: r.row,
+                side: r.side,
+                guess: r.guess,
+            },
+            None => best(deck, table),
+        }
+    }
+}
+
+/// Bets on a random end of a random row.
+pub struct Random;
+
+impl Strategy for Random {
+    fn name(&self) -> String {
+        String::from("random")
+    }
+
+    fn choose(&mut self, _deck: &Deck, table: &Table, rng: &mut dyn RngCore) -> Bet {
+        Bet {
+            row: rng.gen_range(0..table.rows.len()),
+            side: if rng.gen() { Side::Left } else { Side::Right },
+            guess: if rng.gen() {
+                Guess::Higher
+            } else {
+                Guess::Lower
+            },
+        }
+    }
+}
+
+/// Plays the odds of a full deck without counting the cards already
+/// drawn, like a player going by rank alone.
+pub struct HumanLike;
+
+impl Strategy for HumanLike {
+    fn name(&self) -> String {
+        String::from("human-like")
+    }
+
+    fn choose(&mut self, deck: &Deck, table: &Table, _rng: &mut dyn RngCore) -> Bet {
+        let mut full = Deck::from_spec(deck.spec()).unwrap();
+        full.set_tie_rule(deck.tie_rule());
+        best(&full, table)
+    }
+}
+
+fn best(deck: &Deck, table: &Table) -> Bet {
+    let r = recommend(deck, table)[0];
+    Bet {
+        row: r.row,
+        side: r.side,
+        guess: r.guess,
+    }
+}
+
+/// Every built-in strategy.
+pub fn strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(BestOdds),
+        Box::new(Threshold { chance: 0.6 }),
+        Box::new(Random),
+        Box::new(HumanLike),
+    ]
+}
+
+/// Outcome of a single simulated game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Played {
+    /// Cards first dealt to the rows.
+    pub dealt: Vec<Card>,
+    pub won: bool,
+    pub collapses: usize,
+    /// Length of every row at the end of the game.
+    pub rows: Vec<usize>,
+}
+
+/// Totals over many simulated games.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub games: usize,
+    pub wins: usize,
+    pub collapses: usize,
+    pub rows: usize,
+    pub cards: usize,
+}
+
+impl Stats {
+    pub fn record(&mut self, played: &Played) {
+        self.games += 1;
+        self.wins += played.won as usize;
+        self.collapses += played.collapses;
+        self.rows += played.rows.len();
+        self.cards += played.rows.iter().sum::<usize>();
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        ratio(self.wins, self.games)
+    }
+
+    /// Average length of the rows at the end of a game.
+    pub fn average_row_length(&self) -> f32 {
+        ratio(self.cards, self.rows)
+    }
+
+    pub fn collapses_per_game(&self) -> f32 {
+        ratio(self.collapses, self.games)
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f32 {
+    match total {
+        0 => 0.0,
+        total => count as f32 / total as f32,
+    }
+}
+
+/// Settings of the simulated games.
+pub struct Sim {
+    spec: DeckSpec,
+    tie_rule: TieRule,
+    rows: usize,
+    rules: Box<dyn Rules>,
+    lives: usize,
+    seed: u64,
+}
+
+impl Sim {
+    /// Games of 4 rows under target-5 rules with 3 lives, seeded with 0.
+    pub fn new(spec: DeckSpec) -> Sim {
+        Sim {
+            spec,
+            tie_rule: TieRule::default(),
+            rows: 4,
+            rules: Box::new(TargetLength { length: 5 }),
+            lives: 3,
+            seed: 0,
+        }
+    }
+
+    pub fn tie_rule(mut self, tie_rule: TieRule) -> Sim {
+        self.tie_rule = tie_rule;
+        self
+    }
+
+    pub fn rows(mut self, rows: usize) -> Sim {
+        self.rows = rows;
+        self
+    }
+
+    /// Rules deciding the guesses; a complete row wins the game.
+    pub fn rules(mut self, rules: Box<dyn Rules>) -> Sim {
+        self.rules = rules;
+        self
+    }
+
+    /// Collapses losing the game.
+    pub fn lives(mut self, lives: usize) -> Sim {
+        self.lives = lives;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Sim {
+        self.seed = seed;
+        self
+    }
+
+    /// Plays `games` games with the strategy. Every run starts from the
+    /// same seed, so strategies face the same deals.
+    pub fn run(&self, strategy: &mut dyn Strategy, games: usize) -> Result<Stats, Error> {
+        let mut stats = Stats::default();
+        for played in self.games(strategy).take(games) {
+            stats.record(&played?);
+        }
+        Ok(stats)
+    }
+
+    /// Games played one after another. Each shoe is seeded from the deal
+    /// RNG, which the strategy's own RNG leaves untouched.
+    fn games<'a>(
+        &'a self,
+        strategy: &'a mut dyn Strategy,
+    ) -> impl Iterator<Item = Result<Played, Error>> + 'a {
+        let mut deals = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut moves = ChaCha8Rng::seed_from_u64(self.seed);
+        moves.set_stream(1);
+        std::iter::repeat_with(move || self.play(strategy, deals.gen(), &mut moves))
+    }
+
+    /// Plays a single game from a shoe shuffled with `seed`, passing `rng`
+    /// to the strategy.
+    pub fn play(
+        &self,
+        strategy: &mut dyn Strategy,
+        seed: u64,
+        rng: &mut dyn RngCore,
+    ) -> Result<Played, Error> {
+        let mut deck = Deck::from_spec(&self.spec)?;
+        deck.set_tie_rule(self.tie_rule);
+        let mut shoe = Shoe::new(&deck, seed);
+        let dealt = (0..self.rows)
+            .map(|_| shoe.draw(&mut deck))
+            .collect::<Result<Vec<Card>, Error>>()?;
+        let mut table = Table::new(self.rows, dealt.clone())?;
+        let mut collapses = 0;
+        let won = |table: &Table| table.rows.iter().any(|r| self.rules.is_complete(r));
+        while collapses < self.lives && deck.size() > 0 && !won(&table) {
+            let bet = strategy.choose(&deck, &table, rng);
+            let card = shoe.draw(&mut deck)?;
+            let row = match table.rows.get_mut(bet.row) {
+                Some(row) if !self.rules.allows(row, bet.side) => {
+                    return Err(Error::GuessNotAllowed {
+                        row: bet.row,
+                        side: bet.side,
+                    })
+                }
+                Some(row) => row,
+                None => return Err(Error::RowNotFound(bet.row)),
+            };
+            let base = *row.get(bet.side);
+            let outcome = self.rules.outcome(deck.tie_rule(), bet.guess, &base, &card);
+            if shoe.resolve(row, bet.side, outcome, card, &mut deck)? == Command::Collapse {
+                collapses += 1;
+            }
+        }
+        Ok(Played {
+            dealt,
+            won: won(&table),
+            collapses,
+            rows: table.rows.iter().map(|r| r.len()).collect(),
+        })
+    }
+}
+
+/// Table of the stats of every strategy, one line each.
+pub fn report(results: &[(String, Stats)]) -> String {
+    let mut report = format!(
+        "{:<16}{:>10}{:>10}{:>12}\n",
+        "strategy", "win rate", "avg row", "collapses"
+    );
+    for (name, stats) in results {
+        report.push_str(&format!(
+            "{:<16}{:>9.1}%{:>10.2}{:>12.2}\n",
+            name,
+            stats.win_rate() * 100.0,
+            stats.average_row_length(),
+            stats.collapses_per_game()
+        ));
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::{card, deal};
+
+    fn sim() -> Sim {
+        Sim::new(DeckSpec::new()).seed(7)
+    }
+
+    #[test]
+    fn runs_are_reproducible() {
+        let first = sim().run(&mut BestOdds, 200).unwrap();
+        assert_eq!(sim().run(&mut BestOdds, 200), Ok(first));
+        assert_eq!(first.games, 200);
+        assert_eq!(first.rows, 800);
+        assert_ne!(sim().seed(8).run(&mut BestOdds, 200), Ok(first));
+        assert_eq!(sim().rows(0).run(&mut BestOdds, 1), Err(Error::NoRows));
+    }
+
+    #[test]
+    fn strategies_are_dealt_the_same_cards() {
+        let sim = sim();
+        let best = sim.games(&mut BestOdds).nth(5).unwrap().unwrap();
+        let random = sim.games(&mut Random).nth(5).unwrap().unwrap();
+        assert_eq!(best.dealt, random.dealt);
+        assert_eq!(best.dealt.len(), 4);
+        let other = sim.games(&mut BestOdds).nth(6).unwrap().unwrap();
+        assert_ne!(best.dealt, other.dealt);
+    }
+
+    #[test]
+    fn games_end_by_complete_row_or_lives() {
+        let sim = sim().lives(2).rules(Box::new(TargetLength { length: 4 }));
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for strategy in strategies().iter_mut() {
+            for _ in 0..50 {
+                let played = sim.play(strategy.as_mut(), rng.gen(), &mut rng).unwrap();
+                assert_eq!(played.won, played.rows.iter().any(|len| *len >= 4));
+                assert!(played.won || played.collapses == 2);
+                assert!(played.collapses <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn counting_cards_beats_guessing() {
+        let sim = sim();
+        let best = sim.run(&mut BestOdds, 2000).unwrap();
+        let random = sim.run(&mut Random, 2000).unwrap();
+        assert!(best.win_rate() > random.win_rate());
+        assert!(best.collapses_per_game() < random.collapses_per_game());
+        assert!(best.average_row_length() > 1.0);
+    }
+
+    #[test]
+    fn threshold_builds_the_longest_row() {
+        let (mut deck, mut table) = deal(52, &["a2", "b7"]);
+        deck.remove(&card("c8")).unwrap();
+        table.rows[1].add_right(card("c8"));
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let bet = Threshold { chance: 0.5 }.choose(&deck, &table, &mut rng);
+        assert_eq!(bet.row, 1);
+        assert_eq!(bet.side, Side::Left);
+        assert_eq!(BestOdds.choose(&deck, &table, &mut rng).row, 0);
+        let bet = Threshold { chance: 0.99 }.choose(&deck, &table, &mut rng);
+        assert_eq!(bet, BestOdds.choose(&deck, &table, &mut rng));
+    }
+
+    #[test]
+    fn report_lists_every_strategy() {
+        let stats = Stats {
+            games: 4,
+            wins: 1,
+            collapses: 6,
+            rows: 8,
+            cards: 20,
+        };
+        let report = report(&[(String::from("best-odds"), stats)]);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1], "best-odds            25.0%      2.50        1.50");
+    }
+}
@@ -0,0 +1,335 @@
+//! Multi-step lookahead for completing a row of a target length.
+//!
+//! A row pays out `win` once it reaches the target length. Every guess draws
+//! a card that changes the row as
+//! [`Command::from_outcome`](crate::Command::from_outcome), and a collapse
+//! costs `loss`. The player may also stop and collapse the row voluntarily,
+//! which is worth nothing either way.
+//!
+//! The search is exact for the first [`DEPTH`] cards drawn, which keeps it
+//! fast on a full deck. Beyond that a row is valued as if every missing card
+//! were won with the best chance of a single guess.
+//!
+//! All chances and payouts are exact [`Ratio`]s.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::{Deck, Guess, Outcome, Rank, Ratio, Row, Side, Table, TieRule};
+
+/// Value of guessing on one end of a row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveValue {
+    pub side: Side,
+    pub guess: Guess,
+    /// Chance of completing the row when playing for completion afterwards.
+    pub probability: Ratio,
+    /// Expected payout when playing for expected value afterwards.
+    pub expected_value: Ratio,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowAnalysis {
+    pub row: usize,
+    /// Best chance of completing the row.
+    pub probability: Ratio,
+    /// Best expected payout, never below zero since stopping is free.
+    pub expected_value: Ratio,
+    /// Every move, best expected value first.
+    pub moves: Vec<MoveValue>,
+    /// Whether collapsing voluntarily beats every guess.
+    pub stop: bool,
+}
+
+/// Cards the solver looks ahead by default.
+pub const DEPTH: usize = 4;
+
+pub struct Solver {
+    target: usize,
+    win: Ratio,
+    loss: Ratio,
+    depth: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct State {
+    counts: Vec<usize>,
+    left: Rank,
+    right: Rank,
+    len: usize,
+}
+
+struct Search<'a> {
+    solver: &'a Solver,
+    tie_rule: TieRule,
+    ranks: Vec<Rank>,
+    /// Cards in the deck before the search draws any.
+    size: usize,
+    memo: HashMap<State, (Ratio, Ratio)>,
+}
+
+impl Solver {
+    /// Solver for rows paying 1 on reaching `target` cards and costing 1
+    /// on a lost guess, looking [`DEPTH`] cards ahead.
+    pub fn new(target: usize) -> Solver {
+        Solver {
+            target,
+            win: Ratio::ONE,
+            loss: Ratio::ONE,
+            depth: DEPTH,
+        }
+    }
+
+    pub fn payout(mut self, win: Ratio, loss: Ratio) -> Solver {
+        self.win = win;
+        self.loss = loss;
+        self
+    }
+
+    /// Cards to draw in the exact search. Each extra card multiplies the
+    /// work by up to the number of ranks.
+    pub fn depth(mut self, depth: usize) -> Solver {
+        self.depth = depth;
+        self
+    }
+
+    pub fn analyze(&self, deck: &Deck, table: &Table) -> Vec<RowAnalysis> {
+        let mut search = Search::new(self, deck);
+        table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| search.analyze_row(deck, i, row))
+            .collect()
+    }
+
+    pub fn analyze_row(&self, deck: &Deck, row_num: usize, row: &Row) -> RowAnalysis {
+        Search::new(self, deck).analyze_row(deck, row_num, row)
+    }
+}
+
+impl<'a> Search<'a> {
+    fn new(solver: &'a Solver, deck: &Deck) -> Search<'a> {
+        Search {
+            solver,
+            tie_rule: deck.tie_rule(),
+            ranks: deck.ranks().iter().map(|(rank, _)| *rank).collect(),
+            size: deck.size(),
+            memo: HashMap::new(),
+        }
+    }
+
+    fn analyze_row(&mut self, deck: &Deck, row_num: usize, row: &Row) -> RowAnalysis {
+        let state = State {
+            counts: deck.ranks().iter().map(|(_, count)| *count).collect(),
+            left: row.get_left().rank,
+            right: row.get_right().rank,
+            len: row.len(),
+        };
+        let (probability, expected_value) = self.value(&state);
+        let mut moves: Vec<MoveValue> = MOVES
+            .iter()
+            .map(|(side, guess)| {
+                let (probability, expected_value) = self.move_value(&state, *side, *guess);
+                MoveValue {
+                    side: *side,
+                    guess: *guess,
+                    probability,
+                    expected_value,
+                }
+            })
+            .collect();
+        moves.sort_by_key(|m| Reverse(m.expected_value));
+        let stop = state.len < self.solver.target
+            && moves.first().is_none_or(|m| m.expected_value < Ratio::ZERO);
+        RowAnalysis {
+            row: row_num,
+            probability,
+            expected_value,
+            moves,
+            stop,
+        }
+    }
+
+    /// Best completion chance and best expected payout of the state.
+    fn value(&mut self, state: &State) -> (Ratio, Ratio) {
+        if state.len >= self.solver.target {
+            return (Ratio::ONE, self.solver.win);
+        }
+        if let Some(value) = self.memo.get(state) {
+            return *value;
+        }
+        let size: usize = state.counts.iter().sum();
+        if self.size - size >= self.solver.depth {
+            return self.estimate(state, size);
+        }
+        let mut value = (Ratio::ZERO, Ratio::ZERO);
+        for (side, guess) in MOVES {
+            let (probability, expected_value) = self.move_value(state, side, guess);
+            value.0 = value.0.max(probability);
+            value.1 = value.1.max(expected_value);
+        }
+        self.memo.insert(state.clone(), value);
+        value
+    }
+
+    fn move_value(&mut self, state: &State, side: Side, guess: Guess) -> (Ratio, Ratio) {
+        let size: usize = state.counts.iter().sum();
+        if size == 0 {
+            return (Ratio::ZERO, Ratio::ZERO);
+        }
+        let base = match side {
+            Side::Left => state.left,
+            Side::Right => state.right,
+        };
+        let mut probability = Ratio::ZERO;
+        let mut expected_value = Ratio::ZERO;
+        for i in 0..self.ranks.len() {
+            let count = state.counts[i];
+            if count == 0 {
+                continue;
+            }
+            let chance = Ratio::new(count as i128, size as i128);
+            let drawn = self.ranks[i];
+            let mut next = state.clone();
+            next.counts[i] -= 1;
+            match self.tie_rule.outcome(guess, base, drawn) {
+                Outcome::Win => {
+                    match side {
+                        Side::Left => next.left = drawn,
+                        Side::Right => next.right = drawn,
+                    }
+                    next.len += 1;
+                }
+                Outcome::Push => (),
+                Outcome::Lose => {
+                    expected_value = expected_value - chance * self.solver.loss;
+                    continue;
+                }
+            }
+            let (p, ev) = self.value(&next);
+            probability = probability + chance * p;
+            expected_value = expected_value + chance * ev;
+        }
+        (probability, expected_value)
+    }
+
+    /// Value of a state past the search depth, taking every missing card to
+    /// be won with the best chance of a single guess.
+    fn estimate(&self, state: &State, size: usize) -> (Ratio, Ratio) {
+        if size == 0 {
+            return (Ratio::ZERO, Ratio::ZERO);
+        }
+        let wins = MOVES
+            .iter()
+            .map(|(side, guess)| {
+                let base = match side {
+                    Side::Left => state.left,
+                    Side::Right => state.right,
+                };
+                (0..self.ranks.len())
+                    .filter(|i| self.tie_rule.outcome(*guess, base, self.ranks[*i]) == Outcome::Win)
+                    .map(|i| state.counts[i])
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0);
+        let chance = Ratio::new(wins as i128, size as i128);
+        let mut probability = Ratio::ONE;
+        for _ in state.len..self.solver.target {
+            probability = probability * chance;
+        }
+        let expected_value =
+            probability * self.solver.win - (Ratio::ONE - probability) * self.solver.loss;
+        (probability, expected_value.max(Ratio::ZERO))
+    }
+}
+
+const MOVES: [(Side, Guess); 4] = [
+    (Side::Left, Guess::Higher),
+    (Side::Left, Guess::Lower),
+    (Side::Right, Guess::Higher),
+    (Side::Right, Guess::Lower),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::{card, deal};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn solver_looks_one_card_ahead() {
+        let (deck, table) = deal(8, &["a13"]);
+        let analysis = Solver::new(2).analyze(&deck, &table);
+        assert_eq!(analysis[0].probability, Ratio::new(4, 7));
+        assert_eq!(analysis[0].expected_value, Ratio::new(1, 7));
+        assert!(!analysis[0].stop);
+        let best = analysis[0].moves[0];
+        assert_eq!(best.guess, Guess::Higher);
+        assert_eq!(best.expected_value, Ratio::new(1, 7));
+    }
+
+    #[test]
+    fn solver_looks_several_cards_ahead() {
+        let (deck, table) = deal(8, &["a13"]);
+        let analysis = Solver::new(3).analyze(&deck, &table);
+        assert_eq!(analysis[0].probability, Ratio::new(2, 7));
+        assert_eq!(analysis[0].moves[0].expected_value, Ratio::new(-3, 7));
+        assert_eq!(analysis[0].expected_value, Ratio::ZERO);
+        assert!(analysis[0].stop);
+    }
+
+    #[test]
+    fn solver_weighs_payout() {
+        let (deck, table) = deal(8, &["a13"]);
+        let analysis = Solver::new(3)
+            .payout(Ratio::from_integer(3), Ratio::ONE)
+            .analyze(&deck, &table);
+        assert!(!analysis[0].stop);
+        assert_eq!(analysis[0].expected_value, Ratio::new(1, 7));
+    }
+
+    #[test]
+    fn solver_accepts_completed_rows() {
+        let (mut deck, mut table) = deal(8, &["a13"]);
+        deck.remove(&card("a14")).unwrap();
+        table.rows[0].add_right(card("a14"));
+        let analysis = Solver::new(2).analyze(&deck, &table);
+        assert_eq!(analysis[0].probability, Ratio::ONE);
+        assert_eq!(analysis[0].expected_value, Ratio::ONE);
+        assert!(!analysis[0].stop);
+    }
+
+    #[test]
+    fn solver_follows_push_rule() {
+        let (mut deck, table) = deal(8, &["a13"]);
+        deck.set_tie_rule(TieRule::Push);
+        let analysis = Solver::new(2).analyze(&deck, &table);
+        // Ties are discarded until an ace or the deck runs out.
+        assert_eq!(analysis[0].probability, Ratio::ONE);
+        assert_eq!(analysis[0].expected_value, Ratio::ONE);
+    }
+
+    #[test]
+    fn solver_estimates_beyond_its_depth() {
+        let (deck, table) = deal(8, &["a13"]);
+        let analysis = Solver::new(3).depth(0).analyze(&deck, &table);
+        // Both missing cards are taken to be aces, each 4 in 7.
+        assert_eq!(analysis[0].probability, Ratio::new(16, 49));
+        assert_eq!(analysis[0].expected_value, Ratio::ZERO);
+    }
+
+    #[test]
+    fn solver_handles_a_full_deck() {
+        for tie_rule in [TieRule::Lose, TieRule::Push] {
+            let (mut deck, table) = deal(52, &["a8"]);
+            deck.set_tie_rule(tie_rule);
+            let start = Instant::now();
+            let analysis = Solver::new(8).analyze(&deck, &table);
+            assert!(start.elapsed() < Duration::from_secs(20));
+            assert!(analysis[0].probability > Ratio::ZERO);
+            assert!(analysis[0].probability < Ratio::new(1, 5));
+        }
+    }
+}
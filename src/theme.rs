@@ -0,0 +1,401 @@
+use serde::Deserialize;
+use termion::{color, style};
+
+use crate::{Error, Suit};
+
+/// Terminal color, either one of the 16 standard colors or an index into the
+/// 256-color palette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "ColorName")]
+pub enum Color {
+    /// The terminal's own foreground or background.
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    LightBlack,
+    LightRed,
+    LightGreen,
+    LightYellow,
+    LightBlue,
+    LightMagenta,
+    LightCyan,
+    LightWhite,
+    Ansi(u8),
+}
+
+/// Colors are written by name ("light-red") or palette index (208).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorName {
+    Name(String),
+    Ansi(u8),
+}
+
+impl TryFrom<ColorName> for Color {
+    type Error = String;
+
+    fn try_from(name: ColorName) -> Result<Color, String> {
+        let name = match name {
+            ColorName::Ansi(value) => return Ok(Color::Ansi(value)),
+            ColorName::Name(name) => name,
+        };
+        let color = match name.as_str() {
+            "default" => Color::Default,
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            "light-black" => Color::LightBlack,
+            "light-red" => Color::LightRed,
+            "light-green" => Color::LightGreen,
+            "light-yellow" => Color::LightYellow,
+            "light-blue" => Color::LightBlue,
+            "light-magenta" => Color::LightMagenta,
+            "light-cyan" => Color::LightCyan,
+            "light-white" => Color::LightWhite,
+            _ => return Err(format!("unknown color \"{}\"", name)),
+        };
+        Ok(color)
+    }
+}
+
+impl Color {
+    fn ansi(&self) -> Option<u8> {
+        let value = match self {
+            Color::Default => return None,
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::LightBlack => 8,
+            Color::LightRed => 9,
+            Color::LightGreen => 10,
+            Color::LightYellow => 11,
+            Color::LightBlue => 12,
+            Color::LightMagenta => 13,
+            Color::LightCyan => 14,
+            Color::LightWhite => 15,
+            Color::Ansi(value) => *value,
+        };
+        Some(value)
+    }
+
+    /// Escape sequence switching the foreground to this color.
+    pub fn fg(&self) -> String {
+        match self.ansi() {
+            Some(value) => color::Fg(color::AnsiValue(value)).to_string(),
+            None => color::Fg(color::Reset).to_string(),
+        }
+    }
+
+    /// Escape sequence switching the background to this color.
+    pub fn bg(&self) -> String {
+        match self.ansi() {
+            Some(value) => color::Bg(color::AnsiValue(value)).to_string(),
+            None => color::Bg(color::Reset).to_string(),
+        }
+    }
+}
+
+/// Colors for suits, odds and highlights.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub clubs: Color,
+    pub spades: Color,
+    pub hearts: Color,
+    pub diamonds: Color,
+    pub jokers: Color,
+    pub higher: Color,
+    pub equal: Color,
+    pub lower: Color,
+    /// Background of the selected row.
+    pub selected: Color,
+    /// Cards already drawn from the deck.
+    pub drawn: Color,
+    /// Chances below `unlikely_below`.
+    pub unlikely: Color,
+    /// Chances of at least `likely_above`, also shown in bold.
+    pub likely: Color,
+    pub unlikely_below: f32,
+    pub likely_above: f32,
+}
+
+/// Names of the built-in themes.
+pub const PRESETS: [&str; 4] = ["default", "high-contrast", "deuteranopia", "four-color"];
+
+/// Config file contents: a preset and colors replacing some of its own.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    theme: Option<String>,
+    #[serde(default)]
+    colors: Colors,
+    #[serde(default)]
+    heat: Heat,
+}
+
+/// Thresholds for the `unlikely` and `likely` colors.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Heat {
+    unlikely_below: Option<f32>,
+    likely_above: Option<f32>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Colors {
+    clubs: Option<Color>,
+    spades: Option<Color>,
+    hearts: Option<Color>,
+    diamonds: Option<Color>,
+    jokers: Option<Color>,
+    higher: Option<Color>,
+    equal: Option<Color>,
+    lower: Option<Color>,
+    selected: Option<Color>,
+    drawn: Option<Color>,
+    unlikely: Option<Color>,
+    likely: Option<Color>,
+}
+
+impl Theme {
+    /// The built-in theme of this name, see [`PRESETS`].
+    pub fn preset(name: &str) -> Result<Theme, Error> {
+        let default = Theme::default();
+        let theme = match name {
+            "default" => default,
+            "high-contrast" => Theme {
+                clubs: Color::LightWhite,
+                spades: Color::LightWhite,
+                hearts: Color::LightRed,
+                diamonds: Color::LightRed,
+                jokers: Color::LightYellow,
+                higher: Color::LightCyan,
+                equal: Color::LightWhite,
+                lower: Color::LightYellow,
+                selected: Color::Blue,
+                drawn: Color::LightBlack,
+                unlikely: Color::LightRed,
+                likely: Color::LightGreen,
+                ..default
+            },
+            // Blue and orange stay apart for red-green color blindness.
+            "deuteranopia" => Theme {
+                hearts: Color::Ansi(208),
+                diamonds: Color::Ansi(208),
+                higher: Color::LightBlue,
+                lower: Color::Ansi(208),
+                unlikely: Color::Ansi(208),
+                likely: Color::LightBlue,
+                ..default
+            },
+            "four-color" => Theme {
+                clubs: Color::Green,
+                diamonds: Color::Blue,
+                ..default
+            },
+            _ => return Err(Error::UnknownTheme(name.to_string())),
+        };
+        Ok(theme)
+    }
+
+    /// Theme from the TOML config file contents, e.g.
+    ///
+    /// ```toml
+    /// theme = "four-color"
+    ///
+    /// [colors]
+    /// selected = "blue"
+    /// lower = 208
+    ///
+    /// [heat]
+    /// unlikely_below = 0.2
+    /// likely_above = 0.8
+    /// ```
+    ///
+    /// `preset` replaces the config's own `theme`.
+    pub fn load(config: &str, preset: Option<&str>) -> Result<Theme, Error> {
+        let config: Config =
+            toml::from_str(config).map_err(|e| Error::InvalidConfig(e.message().to_string()))?;
+        let mut theme = match preset.or(config.theme.as_deref()) {
+            Some(name) => Theme::preset(name)?,
+            None => Theme::default(),
+        };
+        let colors = config.colors;
+        let overrides = [
+            (&mut theme.clubs, colors.clubs),
+            (&mut theme.spades, colors.spades),
+            (&mut theme.hearts, colors.hearts),
+            (&mut theme.diamonds, colors.diamonds),
+            (&mut theme.jokers, colors.jokers),
+            (&mut theme.higher, colors.higher),
+            (&mut theme.equal, colors.equal),
+            (&mut theme.lower, colors.lower),
+            (&mut theme.selected, colors.selected),
+            (&mut theme.drawn, colors.drawn),
+            (&mut theme.unlikely, colors.unlikely),
+            (&mut theme.likely, colors.likely),
+        ];
+        for (color, replacement) in overrides {
+            if let Some(replacement) = replacement {
+                *color = replacement;
+            }
+        }
+        if let Some(threshold) = config.heat.unlikely_below {
+            theme.unlikely_below = threshold;
+        }
+        if let Some(threshold) = config.heat.likely_above {
+            theme.likely_above = threshold;
+        }
+        Ok(theme)
+    }
+
+    /// Colors `text` showing a chance by how likely the chance is, falling
+    /// back to `base` between the thresholds.
+    pub fn heat(&self, text: &str, chance: f32, base: Color) -> String {
+        if chance >= self.likely_above {
+            // Normal intensity (22) also ends bold text.
+            return format!(
+                "{}{}{}{}",
+                self.likely.fg(),
+                style::Bold,
+                text,
+                style::NoFaint
+            );
+        }
+        let color = match chance < self.unlikely_below {
+            true => self.unlikely,
+            false => base,
+        };
+        format!("{}{}", color.fg(), text)
+    }
+
+    pub fn suit(&self, suit: Suit) -> Color {
+        match suit {
+            Suit::Clubs => self.clubs,
+            Suit::Spades => self.spades,
+            Suit::Hearts => self.hearts,
+            Suit::Diamonds => self.diamonds,
+            Suit::Joker => self.jokers,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            clubs: Color::Default,
+            spades: Color::Default,
+            hearts: Color::Red,
+            diamonds: Color::Red,
+            jokers: Color::Default,
+            higher: Color::Green,
+            equal: Color::Default,
+            lower: Color::Blue,
+            selected: Color::LightBlack,
+            drawn: Color::LightBlack,
+            unlikely: Color::Red,
+            likely: Color::Green,
+            unlikely_below: 0.25,
+            likely_above: 0.75,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn presets_exist() {
+        for name in PRESETS {
+            assert!(Theme::preset(name).is_ok());
+        }
+        assert_eq!(
+            Theme::preset("sepia"),
+            Err(Error::UnknownTheme(String::from("sepia")))
+        );
+        let deuteranopia = Theme::preset("deuteranopia").unwrap();
+        for color in [deuteranopia.hearts, deuteranopia.higher, deuteranopia.lower] {
+            assert!(![Color::Red, Color::Green].contains(&color));
+        }
+    }
+
+    #[test]
+    fn colors_match_termion() {
+        assert_eq!(Color::Red.fg(), color::Fg(color::Red).to_string());
+        assert_eq!(
+            Color::LightBlack.bg(),
+            color::Bg(color::LightBlack).to_string()
+        );
+        assert_eq!(Color::Default.fg(), color::Fg(color::Reset).to_string());
+        assert_eq!(Color::Ansi(208).fg(), "\x1b[38;5;208m");
+    }
+
+    #[test]
+    fn theme_loads_from_config() {
+        assert_eq!(Theme::load("", None), Ok(Theme::default()));
+        let config = "theme = \"four-color\"\n\n[colors]\nselected = \"blue\"\nlower = 208\n";
+        let theme = Theme::load(config, None).unwrap();
+        assert_eq!(theme.clubs, Color::Green);
+        assert_eq!(theme.selected, Color::Blue);
+        assert_eq!(theme.lower, Color::Ansi(208));
+        assert_eq!(theme.hearts, Color::Red);
+        let theme = Theme::load(config, Some("high-contrast")).unwrap();
+        assert_eq!(theme.clubs, Color::LightWhite);
+        assert_eq!(theme.selected, Color::Blue);
+    }
+
+    #[test]
+    fn heat_follows_thresholds() {
+        let theme = Theme::load("[heat]\nunlikely_below = 0.1\n", None).unwrap();
+        assert_eq!(theme.unlikely_below, 0.1);
+        assert_eq!(theme.likely_above, 0.75);
+        assert_eq!(
+            theme.heat("x", 0.05, Color::Blue),
+            format!("{}x", Color::Red.fg())
+        );
+        assert_eq!(
+            theme.heat("x", 0.5, Color::Blue),
+            format!("{}x", Color::Blue.fg())
+        );
+        assert_eq!(
+            theme.heat("x", 0.75, Color::Blue),
+            format!("{}{}x{}", Color::Green.fg(), style::Bold, style::NoFaint)
+        );
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        for config in [
+            "theme = 4",
+            "[colors]\nhearts = \"pink\"",
+            "colour = 1",
+            "[heat]\nlikely = 0.5",
+        ] {
+            assert!(matches!(
+                Theme::load(config, None),
+                Err(Error::InvalidConfig(_))
+            ));
+        }
+        assert_eq!(
+            Theme::load("theme = \"sepia\"", None),
+            Err(Error::UnknownTheme(String::from("sepia")))
+        );
+    }
+}